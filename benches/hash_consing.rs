@@ -0,0 +1,47 @@
+//! Compares canonicalizing a large tree with shared subtrees built plainly
+//! (one `Arc` per construction) against one built through `ExprBuilder`
+//! (structural sharing via hash-consing).
+
+use std::hint::black_box;
+use std::sync::Arc;
+
+use cation::core_ir::{Canonical, Expr, ExprBuilder, Pauli, PauliString};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const WIDTH: usize = 200;
+const DEPTH: usize = 6;
+
+fn plain_tree(depth: usize) -> Arc<Expr> {
+    let leaves: Vec<Arc<Expr>> = (0..WIDTH)
+        .map(|q| Arc::new(Expr::Pauli(PauliString::new([(q, Pauli::X)]))))
+        .collect();
+    let mut tree = Arc::new(Expr::Sum(leaves));
+    for _ in 0..depth {
+        tree = Arc::new(Expr::Product(vec![tree.clone(), tree]));
+    }
+    tree
+}
+
+fn built_tree(depth: usize) -> Arc<Expr> {
+    let mut builder = ExprBuilder::new();
+    let leaves: Vec<Arc<Expr>> = (0..WIDTH)
+        .map(|q| builder.pauli(PauliString::new([(q, Pauli::X)])))
+        .collect();
+    let mut tree = builder.sum(leaves);
+    for _ in 0..depth {
+        tree = builder.product(vec![tree.clone(), tree]);
+    }
+    tree
+}
+
+fn bench_canonicalize(c: &mut Criterion) {
+    c.bench_function("canonicalize plain tree", |b| {
+        b.iter(|| black_box(plain_tree(DEPTH)).canonical())
+    });
+    c.bench_function("canonicalize hash-consed tree", |b| {
+        b.iter(|| black_box(built_tree(DEPTH)).canonical())
+    });
+}
+
+criterion_group!(benches, bench_canonicalize);
+criterion_main!(benches);
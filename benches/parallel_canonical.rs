@@ -0,0 +1,34 @@
+//! Compares sequential vs. (feature-gated) `rayon`-parallel canonicalization
+//! of a large sum. Run with `--features parallel` to exercise the parallel
+//! path; without it, both benchmark functions run the same sequential code.
+
+use std::sync::Arc;
+
+use cation::core_ir::{Canonical, Expr, Pauli, PauliString, Symbol};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const TERM_COUNT: usize = 5_000;
+
+fn large_sum() -> Expr {
+    let terms: Vec<Arc<Expr>> = (0..TERM_COUNT)
+        .map(|i| {
+            let shuffled = (i * 257 + 13) % TERM_COUNT;
+            match shuffled % 3 {
+                0 => Arc::new(Expr::Scalar(shuffled as f64)),
+                1 => Arc::new(Expr::Symbol(Symbol::new(&format!("x{shuffled}")))),
+                _ => Arc::new(Expr::Pauli(PauliString::new([(shuffled, Pauli::X)]))),
+            }
+        })
+        .collect();
+    Expr::Sum(terms)
+}
+
+fn bench_canonicalize(c: &mut Criterion) {
+    let sum = large_sum();
+    c.bench_function("canonicalize large sum", |b| {
+        b.iter(|| sum.canonical());
+    });
+}
+
+criterion_group!(benches, bench_canonicalize);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+//! Benchmarks constructing many weight-2 `PauliString`s, the common case the
+//! `SmallVec`-backed `ops` storage is meant to keep allocation-free.
+
+use std::hint::black_box;
+
+use cation::core_ir::{Pauli, PauliString};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const COUNT: usize = 100_000;
+
+fn bench_construct_weight_two(c: &mut Criterion) {
+    c.bench_function("construct weight-2 PauliStrings", |b| {
+        b.iter(|| {
+            for i in 0..COUNT {
+                black_box(PauliString::new([(i, Pauli::X), (i + 1, Pauli::Z)]));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_construct_weight_two);
+criterion_main!(benches);
@@ -0,0 +1,155 @@
+//! Clifford gate conjugation of [`PauliString`]s: the Pauli-frame updates
+//! stabilizer simulators use to track how an operator transforms under H,
+//! S, and CNOT, without ever expanding either the gate or the operator to
+//! a dense matrix.
+
+use crate::core_ir::{Pauli, PauliString, Phase};
+
+/// Rebuilds `p` with `qubit`'s operator replaced by `new_op` (or dropped,
+/// if `new_op` is [`Pauli::I`]), leaving every other qubit untouched.
+fn replace_qubit(p: &PauliString, qubit: usize, new_op: Pauli) -> PauliString {
+    let ops = p
+        .iter()
+        .filter(|&(q, _)| q != qubit)
+        .map(|(q, pauli)| (q, *pauli))
+        .chain((new_op != Pauli::I).then_some((qubit, new_op)));
+    PauliString::new(ops)
+}
+
+/// Conjugates `p` by a Hadamard gate on `qubit`: `H P H = P'`. Swaps `X`
+/// and `Z` on that qubit and picks up a `-1` on `Y`, since `H Y H = -Y`.
+pub fn conjugate_h(p: &PauliString, qubit: usize) -> (Phase, PauliString) {
+    let (phase, mapped) = match p.get(qubit) {
+        Pauli::I => (Phase::One, Pauli::I),
+        Pauli::X => (Phase::One, Pauli::Z),
+        Pauli::Z => (Phase::One, Pauli::X),
+        Pauli::Y => (Phase::MinusOne, Pauli::Y),
+    };
+    (phase, replace_qubit(p, qubit, mapped))
+}
+
+/// Conjugates `p` by a phase (`S`) gate on `qubit`: `S P S^\dagger = P'`.
+/// Maps `X -> Y` and `Y -> -X`, and leaves `Z` fixed.
+pub fn conjugate_s(p: &PauliString, qubit: usize) -> (Phase, PauliString) {
+    let (phase, mapped) = match p.get(qubit) {
+        Pauli::I => (Phase::One, Pauli::I),
+        Pauli::X => (Phase::One, Pauli::Y),
+        Pauli::Y => (Phase::MinusOne, Pauli::X),
+        Pauli::Z => (Phase::One, Pauli::Z),
+    };
+    (phase, replace_qubit(p, qubit, mapped))
+}
+
+/// Conjugates `p` by a CNOT gate with the given `control` and `target`
+/// qubits: `CNOT P CNOT = P'`. Works in the symplectic (X-bit, Z-bit)
+/// picture also used by [`PauliString::to_symplectic`]: the target's
+/// X-bit picks up the control's, and the control's Z-bit picks up the
+/// target's. Unlike `H` and `S`, CNOT conjugation never introduces a sign.
+pub fn conjugate_cnot(p: &PauliString, control: usize, target: usize) -> (Phase, PauliString) {
+    fn bits(pauli: Pauli) -> (bool, bool) {
+        match pauli {
+            Pauli::I => (false, false),
+            Pauli::X => (true, false),
+            Pauli::Z => (false, true),
+            Pauli::Y => (true, true),
+        }
+    }
+    fn from_bits(x: bool, z: bool) -> Pauli {
+        match (x, z) {
+            (false, false) => Pauli::I,
+            (true, false) => Pauli::X,
+            (false, true) => Pauli::Z,
+            (true, true) => Pauli::Y,
+        }
+    }
+
+    let (xc, zc) = bits(p.get(control));
+    let (xt, zt) = bits(p.get(target));
+    let new_control = from_bits(xc, zc ^ zt);
+    let new_target = from_bits(xt ^ xc, zt);
+
+    let updated = replace_qubit(p, control, new_control);
+    (Phase::One, replace_qubit(&updated, target, new_target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn h_swaps_x_and_z() {
+        let x0 = PauliString::new([(0, Pauli::X)]);
+        assert_eq!(conjugate_h(&x0, 0), (Phase::One, PauliString::new([(0, Pauli::Z)])));
+
+        let z0 = PauliString::new([(0, Pauli::Z)]);
+        assert_eq!(conjugate_h(&z0, 0), (Phase::One, PauliString::new([(0, Pauli::X)])));
+    }
+
+    #[test]
+    fn h_negates_y() {
+        let y0 = PauliString::new([(0, Pauli::Y)]);
+        assert_eq!(conjugate_h(&y0, 0), (Phase::MinusOne, y0));
+    }
+
+    #[test]
+    fn h_leaves_other_qubits_untouched() {
+        let x0z1 = PauliString::new([(0, Pauli::X), (1, Pauli::Z)]);
+        let (phase, conjugated) = conjugate_h(&x0z1, 0);
+        assert_eq!(phase, Phase::One);
+        assert_eq!(conjugated, PauliString::new([(0, Pauli::Z), (1, Pauli::Z)]));
+    }
+
+    #[test]
+    fn s_maps_x_to_y() {
+        let x0 = PauliString::new([(0, Pauli::X)]);
+        assert_eq!(conjugate_s(&x0, 0), (Phase::One, PauliString::new([(0, Pauli::Y)])));
+    }
+
+    #[test]
+    fn s_maps_y_to_minus_x() {
+        let y0 = PauliString::new([(0, Pauli::Y)]);
+        assert_eq!(conjugate_s(&y0, 0), (Phase::MinusOne, PauliString::new([(0, Pauli::X)])));
+    }
+
+    #[test]
+    fn s_leaves_z_fixed() {
+        let z0 = PauliString::new([(0, Pauli::Z)]);
+        assert_eq!(conjugate_s(&z0, 0), (Phase::One, z0));
+    }
+
+    #[test]
+    fn cnot_spreads_control_x_onto_target() {
+        let x0 = PauliString::new([(0, Pauli::X)]);
+        assert_eq!(
+            conjugate_cnot(&x0, 0, 1),
+            (Phase::One, PauliString::new([(0, Pauli::X), (1, Pauli::X)]))
+        );
+    }
+
+    #[test]
+    fn cnot_spreads_target_z_onto_control() {
+        let z1 = PauliString::new([(1, Pauli::Z)]);
+        assert_eq!(
+            conjugate_cnot(&z1, 0, 1),
+            (Phase::One, PauliString::new([(0, Pauli::Z), (1, Pauli::Z)]))
+        );
+    }
+
+    #[test]
+    fn cnot_fixes_control_z_and_target_x() {
+        let z0 = PauliString::new([(0, Pauli::Z)]);
+        assert_eq!(conjugate_cnot(&z0, 0, 1), (Phase::One, z0));
+
+        let x1 = PauliString::new([(1, Pauli::X)]);
+        assert_eq!(conjugate_cnot(&x1, 0, 1), (Phase::One, x1));
+    }
+
+    #[test]
+    fn cnot_spreads_control_y_onto_target_as_x() {
+        let y0 = PauliString::new([(0, Pauli::Y)]);
+        assert_eq!(
+            conjugate_cnot(&y0, 0, 1),
+            (Phase::One, PauliString::new([(0, Pauli::Y), (1, Pauli::X)]))
+        );
+    }
+}
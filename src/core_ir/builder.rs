@@ -0,0 +1,113 @@
+//! Hash-consing builder for `Expr` trees.
+//!
+//! Building expressions directly with `Arc::new` allocates a fresh node
+//! even when the subtree is structurally identical to one built earlier.
+//! `ExprBuilder` caches every node it constructs, so building the same
+//! subtree twice through the same builder returns the identical `Arc`,
+//! letting callers use `Arc::ptr_eq` as a cheap equality fast path instead
+//! of always falling back to deep structural comparison. The cache lives
+//! on the builder instance rather than behind global state, so builders
+//! can be scoped per-task and dropped without leaking.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::expr::Expr;
+use super::pauli_string::PauliString;
+use super::symbol::Symbol;
+
+/// Constructs `Expr` trees with structural sharing.
+#[derive(Debug, Default)]
+pub struct ExprBuilder {
+    cache: HashMap<Expr, Arc<Expr>>,
+}
+
+impl ExprBuilder {
+    /// An empty builder with no interned nodes yet.
+    pub fn new() -> ExprBuilder {
+        ExprBuilder::default()
+    }
+
+    /// The number of distinct nodes interned so far.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Whether no nodes have been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    fn intern(&mut self, expr: Expr) -> Arc<Expr> {
+        if let Some(existing) = self.cache.get(&expr) {
+            return existing.clone();
+        }
+        let arc = Arc::new(expr.clone());
+        self.cache.insert(expr, arc.clone());
+        arc
+    }
+
+    /// Interns a `Scalar` leaf.
+    pub fn scalar(&mut self, value: f64) -> Arc<Expr> {
+        self.intern(Expr::Scalar(value))
+    }
+
+    /// Interns a `Symbol` leaf.
+    pub fn symbol(&mut self, symbol: Symbol) -> Arc<Expr> {
+        self.intern(Expr::Symbol(symbol))
+    }
+
+    /// Interns a `Pauli` leaf.
+    pub fn pauli(&mut self, pauli: PauliString) -> Arc<Expr> {
+        self.intern(Expr::Pauli(pauli))
+    }
+
+    /// Interns a `Complex` leaf representing `re + im*i`.
+    pub fn complex(&mut self, re: f64, im: f64) -> Arc<Expr> {
+        self.intern(Expr::Complex(re, im))
+    }
+
+    /// Interns a `Sum` node over the given terms.
+    pub fn sum(&mut self, terms: Vec<Arc<Expr>>) -> Arc<Expr> {
+        self.intern(Expr::Sum(terms))
+    }
+
+    /// Interns a `Product` node over the given factors.
+    pub fn product(&mut self, factors: Vec<Arc<Expr>>) -> Arc<Expr> {
+        self.intern(Expr::Product(factors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_ir::Pauli;
+
+    #[test]
+    fn identical_subtrees_share_a_pointer() {
+        let mut builder = ExprBuilder::new();
+        let a = builder.scalar(2.0);
+        let b = builder.scalar(2.0);
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn identical_sums_of_shared_children_share_a_pointer() {
+        let mut builder = ExprBuilder::new();
+        let x0 = builder.pauli(PauliString::new([(0, Pauli::X)]));
+        let theta = builder.symbol(Symbol::new("theta"));
+
+        let first = builder.sum(vec![x0.clone(), theta.clone()]);
+        let second = builder.sum(vec![x0, theta]);
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn distinct_subtrees_get_distinct_pointers() {
+        let mut builder = ExprBuilder::new();
+        let a = builder.scalar(1.0);
+        let b = builder.scalar(2.0);
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(builder.len(), 2);
+    }
+}
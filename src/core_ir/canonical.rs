@@ -0,0 +1,513 @@
+//! Structural canonicalization of [`Expr`] trees.
+//!
+//! Canonical form only flattens nested sums/products and sorts children by
+//! the total order on [`Expr`]; it never folds constants or merges like
+//! terms (that's [`Expr::simplify`] and future algebraic canonicalization).
+//! Two expressions that are equal up to reordering of commuting sum/product
+//! children compare equal after canonicalizing.
+
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use super::expr::Expr;
+
+/// An [`Expr`] that has been put into canonical form, with a structural
+/// hash precomputed at construction time so `PartialEq` can fast-reject
+/// unequal values before paying for a full structural comparison. Two
+/// `Canonicalized` values compare equal exactly when their source
+/// expressions are equal up to reordering of sum/product children.
+#[derive(Debug, Clone)]
+pub struct Canonicalized<T> {
+    value: T,
+    hash: u64,
+}
+
+impl<T> Canonicalized<T> {
+    /// Computes and caches the structural hash of `value`.
+    fn new(value: T) -> Canonicalized<T>
+    where
+        T: Hash,
+    {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        Canonicalized { value, hash }
+    }
+
+    /// Unwraps back to the plain canonical value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> std::ops::Deref for Canonicalized<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Canonicalized<T> {
+    /// Compares the cached hash first: a mismatch proves inequality without
+    /// looking at `value` at all, which is the whole point for large trees.
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Canonicalized<T> {}
+
+impl<T> Hash for Canonicalized<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+/// Flattens nested `Sum`-of-`Sum` and `Product`-of-`Product` structure
+/// without folding or reordering anything.
+pub trait Flatten {
+    fn flatten(&self) -> Self;
+}
+
+impl Flatten for Expr {
+    fn flatten(&self) -> Expr {
+        match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => self.clone(),
+            Expr::Sum(terms) => {
+                let mut flat = Vec::with_capacity(terms.len());
+                for term in terms {
+                    match term.flatten() {
+                        Expr::Sum(inner) => flat.extend(inner),
+                        other => flat.push(Arc::new(other)),
+                    }
+                }
+                Expr::Sum(flat)
+            }
+            Expr::Product(factors) => {
+                let mut flat = Vec::with_capacity(factors.len());
+                for factor in factors {
+                    match factor.flatten() {
+                        Expr::Product(inner) => flat.extend(inner),
+                        other => flat.push(Arc::new(other)),
+                    }
+                }
+                Expr::Product(flat)
+            }
+        }
+    }
+}
+
+/// Produces a [`Canonicalized`] form of an expression.
+///
+/// Canonicalizing is idempotent: `x.canonical().into_inner().canonical() ==
+/// x.canonical()` for any `x`, since flattening an already-flat tree is a
+/// no-op, recursively canonicalizing an already-canonical child returns it
+/// unchanged, and a `Sum`/`Product` only keeps its wrapper once it has more
+/// than one child (a lone child is always unwrapped down to a bare leaf or
+/// sub-tree), so there's no multi-child node left for a second pass to
+/// re-collapse. Everything downstream (e.g. [`Canonicalized`]'s cached-hash
+/// equality) relies on this.
+///
+/// ```
+/// use cation::core_ir::{Canonical, Expr, Pauli, PauliString};
+/// use std::sync::Arc;
+///
+/// let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+/// let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+/// let a = Expr::Sum(vec![x0.clone(), z1.clone()]);
+/// let b = Expr::Sum(vec![z1, x0]);
+/// assert_eq!(a.canonical(), b.canonical());
+/// ```
+pub trait Canonical {
+    fn canonical(&self) -> Canonicalized<Expr>;
+}
+
+impl Canonical for Expr {
+    fn canonical(&self) -> Canonicalized<Expr> {
+        Canonicalized::new(canonical_inner(self))
+    }
+}
+
+/// A singleton sum is just its element; an empty sum is the additive
+/// identity, `0`. Keeps canonical forms minimal and comparable.
+fn unwrap_sum(mut terms: Vec<Arc<Expr>>) -> Expr {
+    match terms.len() {
+        0 => Expr::Scalar(0.0),
+        1 => terms.pop().unwrap().as_ref().clone(),
+        _ => Expr::Sum(terms),
+    }
+}
+
+/// A singleton product is just its element; an empty product is the
+/// multiplicative identity, `1`.
+fn unwrap_product(mut factors: Vec<Arc<Expr>>) -> Expr {
+    match factors.len() {
+        0 => Expr::Scalar(1.0),
+        1 => factors.pop().unwrap().as_ref().clone(),
+        _ => Expr::Product(factors),
+    }
+}
+
+fn canonical_inner(expr: &Expr) -> Expr {
+    match expr.flatten() {
+        Expr::Sum(terms) => {
+            let mut canon = canonicalize_children(&terms);
+            canon.sort();
+            unwrap_sum(canon)
+        }
+        Expr::Product(factors) => {
+            // Unlike `Sum`, a `Product`'s factors are never reordered here:
+            // operators don't commute in general, so only flattening is
+            // safe. Sorting runs of factors that *do* commute is a
+            // dedicated, opt-in step (see `Expr::canonical_commuting_sorted`).
+            let canon = canonicalize_children(&factors);
+            unwrap_product(canon)
+        }
+        leaf => leaf,
+    }
+}
+
+/// Above this many children, the `parallel` feature forks via `rayon`
+/// instead of mapping sequentially; below it the fork overhead isn't worth
+/// paying.
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 64;
+
+/// Recursively canonicalizes `children`, in parallel via `rayon` when the
+/// `parallel` feature is enabled and there are enough of them to be worth
+/// it. Output is identical to the sequential map either way — canonical
+/// form doesn't depend on iteration order.
+fn canonicalize_children(children: &[Arc<Expr>]) -> Vec<Arc<Expr>> {
+    #[cfg(feature = "parallel")]
+    {
+        if children.len() > PARALLEL_THRESHOLD {
+            return canonicalize_children_parallel(children);
+        }
+    }
+    canonicalize_children_sequential(children)
+}
+
+fn canonicalize_children_sequential(children: &[Arc<Expr>]) -> Vec<Arc<Expr>> {
+    children.iter().map(|child| Arc::new(canonical_inner(child))).collect()
+}
+
+#[cfg(feature = "parallel")]
+fn canonicalize_children_parallel(children: &[Arc<Expr>]) -> Vec<Arc<Expr>> {
+    use rayon::prelude::*;
+
+    children
+        .par_iter()
+        .map(|child| Arc::new(canonical_inner(child)))
+        .collect()
+}
+
+/// Structural [`canonical_inner`] plus algebraic merging: identical summands
+/// (up to a leading scalar coefficient) are combined into one
+/// scalar-weighted term, and adjacent `Pauli` factors within a product are
+/// multiplied together (always valid, since their order is preserved),
+/// folding any non-real phase the multiplication produces (e.g. `X0 * Y0
+/// = i*Z0`) into an `Expr::Complex` coefficient instead of discarding it.
+/// Unlike `canonical_inner`, this does change algebraic structure, so it's
+/// kept as a separate entry point rather than folded into `Canonical`.
+pub(crate) fn canonical_algebraic_inner(expr: &Expr) -> Expr {
+    merge_algebraic(&canonical_inner(expr))
+}
+
+fn merge_algebraic(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => expr.clone(),
+        Expr::Sum(terms) => {
+            let mut acc: BTreeMap<Expr, f64> = BTreeMap::new();
+            for term in terms {
+                let (coeff, remainder) = split_coefficient(&merge_algebraic(term));
+                *acc.entry(remainder).or_insert(0.0) += coeff;
+            }
+            let mut rebuilt: Vec<Arc<Expr>> = acc
+                .into_iter()
+                .filter(|(_, coeff)| *coeff != 0.0)
+                .map(|(remainder, coeff)| {
+                    if remainder == Expr::Scalar(1.0) {
+                        Arc::new(Expr::Scalar(coeff))
+                    } else if coeff == 1.0 {
+                        Arc::new(remainder)
+                    } else {
+                        Arc::new(Expr::Product(vec![
+                            Arc::new(Expr::Scalar(coeff)),
+                            Arc::new(remainder),
+                        ]))
+                    }
+                })
+                .collect();
+            rebuilt.sort();
+            match rebuilt.len() {
+                0 => Expr::Scalar(0.0),
+                1 => rebuilt.into_iter().next().unwrap().as_ref().clone(),
+                _ => Expr::Sum(rebuilt),
+            }
+        }
+        Expr::Product(factors) => {
+            let mut re_acc = 1.0;
+            let mut im_acc = 0.0;
+            let mut rest: Vec<Expr> = Vec::with_capacity(factors.len());
+            for factor in factors {
+                match merge_algebraic(factor) {
+                    Expr::Scalar(v) => {
+                        re_acc *= v;
+                        im_acc *= v;
+                    }
+                    Expr::Complex(re, im) => {
+                        let new_re = re_acc * re - im_acc * im;
+                        let new_im = re_acc * im + im_acc * re;
+                        re_acc = new_re;
+                        im_acc = new_im;
+                    }
+                    Expr::Pauli(p) => match rest.last_mut() {
+                        Some(Expr::Pauli(prev)) => {
+                            let (phase, product) = prev.multiply(&p);
+                            let (re, im) = phase.to_complex();
+                            let new_re = re_acc * re - im_acc * im;
+                            let new_im = re_acc * im + im_acc * re;
+                            re_acc = new_re;
+                            im_acc = new_im;
+                            *prev = product;
+                        }
+                        _ => rest.push(Expr::Pauli(p)),
+                    },
+                    other => rest.push(other),
+                }
+            }
+            let mut out: Vec<Arc<Expr>> = Vec::with_capacity(rest.len() + 1);
+            if re_acc != 1.0 || im_acc != 0.0 || rest.is_empty() {
+                let coeff = if im_acc == 0.0 { Expr::Scalar(re_acc) } else { Expr::Complex(re_acc, im_acc) };
+                out.push(Arc::new(coeff));
+            }
+            out.extend(rest.into_iter().map(Arc::new));
+            if out.len() == 1 {
+                out.into_iter().next().unwrap().as_ref().clone()
+            } else {
+                Expr::Product(out)
+            }
+        }
+    }
+}
+
+/// Like `canonical_inner`, but additionally sorts maximal runs of
+/// pairwise-commuting factors within each product into their `Ord` order,
+/// leaving non-commuting neighbors fixed. Kept separate from
+/// `canonical_inner` (which never reorders products) so existing callers
+/// are unaffected; gated behind `Expr::canonical_commuting_sorted`.
+pub(crate) fn canonical_commuting_sorted_inner(expr: &Expr) -> Expr {
+    match expr.flatten() {
+        Expr::Sum(mut terms) => {
+            let mut canon: Vec<Arc<Expr>> = terms
+                .drain(..)
+                .map(|t| Arc::new(canonical_commuting_sorted_inner(&t)))
+                .collect();
+            canon.sort();
+            unwrap_sum(canon)
+        }
+        Expr::Product(mut factors) => {
+            let canon: Vec<Arc<Expr>> = factors
+                .drain(..)
+                .map(|f| Arc::new(canonical_commuting_sorted_inner(&f)))
+                .collect();
+            unwrap_product(sort_commuting_runs(canon))
+        }
+        leaf => leaf,
+    }
+}
+
+fn factors_commute(a: &Expr, b: &Expr) -> bool {
+    match (a, b) {
+        (Expr::Pauli(p), Expr::Pauli(q)) => p.commutes_with(q),
+        _ => true,
+    }
+}
+
+/// Bubble-sorts adjacent factors, but only swaps a pair that commutes, so a
+/// non-commuting neighbor acts as a fence the sort can't cross.
+fn sort_commuting_runs(factors: Vec<Arc<Expr>>) -> Vec<Arc<Expr>> {
+    let mut factors = factors;
+    let n = factors.len();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..n.saturating_sub(1) {
+            if factors[i] > factors[i + 1] && factors_commute(&factors[i], &factors[i + 1]) {
+                factors.swap(i, i + 1);
+                changed = true;
+            }
+        }
+    }
+    factors
+}
+
+/// Splits a single-scalar-coefficient `Product` into `(coefficient,
+/// remainder)`; any other expression has coefficient `1.0`.
+fn split_coefficient(expr: &Expr) -> (f64, Expr) {
+    match expr {
+        Expr::Scalar(v) => (*v, Expr::Scalar(1.0)),
+        Expr::Product(factors) => match factors.first().map(|f| f.as_ref()) {
+            Some(Expr::Scalar(v)) => {
+                let rest = &factors[1..];
+                let remainder = if rest.len() == 1 {
+                    rest[0].as_ref().clone()
+                } else {
+                    Expr::Product(rest.to_vec())
+                };
+                (*v, remainder)
+            }
+            _ => (1.0, expr.clone()),
+        },
+        _ => (1.0, expr.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_ir::pauli::Pauli;
+    use crate::core_ir::pauli_string::PauliString;
+    use crate::core_ir::symbol::Symbol;
+
+    #[test]
+    fn canonical_sorts_scalar_symbol_pauli_into_fixed_order() {
+        let pauli = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let symbol = Arc::new(Expr::Symbol(Symbol::new("theta")));
+        let scalar = Arc::new(Expr::Scalar(1.0));
+
+        let sum = Expr::Sum(vec![pauli.clone(), symbol.clone(), scalar.clone()]);
+        let Expr::Sum(ordered) = sum.canonical().into_inner() else {
+            panic!("expected Sum");
+        };
+        assert_eq!(ordered, vec![scalar, symbol, pauli]);
+    }
+
+    #[test]
+    fn canonical_is_order_independent() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let a = Expr::Sum(vec![x0.clone(), z1.clone()]);
+        let b = Expr::Sum(vec![z1, x0]);
+        assert_eq!(a.canonical(), b.canonical());
+    }
+
+    #[test]
+    fn canonical_with_nan_scalar_is_deterministic_and_keeps_term_count() {
+        // `Expr`'s derived `PartialEq` compares `f64` fields with IEEE `==`,
+        // under which `NaN != NaN`, so we can't `assert_eq!` the canonical
+        // forms directly; comparing their `Debug` output instead still
+        // catches any nondeterminism in where `total_cmp` places the `NaN`.
+        let sum = Expr::Sum(vec![
+            Arc::new(Expr::Scalar(f64::NAN)),
+            Arc::new(Expr::Scalar(2.0)),
+            Arc::new(Expr::Symbol(Symbol::new("a"))),
+        ]);
+
+        let first = sum.canonical();
+        let second = sum.canonical();
+        assert_eq!(format!("{first:?}"), format!("{second:?}"));
+        let Expr::Sum(terms) = first.into_inner() else {
+            panic!("expected Sum");
+        };
+        assert_eq!(terms.len(), 3);
+    }
+
+    #[test]
+    fn differently_ordered_equal_sums_hash_identically() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let a = Expr::Sum(vec![x0.clone(), z1.clone()]).canonical();
+        let b = Expr::Sum(vec![z1, x0]).canonical();
+
+        let hash_of = |c: &Canonicalized<Expr>| {
+            let mut hasher = DefaultHasher::new();
+            c.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash_of(&a), hash_of(&b));
+    }
+
+    #[test]
+    fn canonical_removes_empty_and_singleton_sum_product_nodes() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+
+        assert_eq!(Expr::Sum(vec![x0.clone()]).canonical(), Canonicalized::new(x0.as_ref().clone()));
+        assert_eq!(Expr::Product(vec![x0.clone()]).canonical(), Canonicalized::new(x0.as_ref().clone()));
+        assert_eq!(Expr::Sum(vec![]).canonical(), Canonicalized::new(Expr::Scalar(0.0)));
+        assert_eq!(Expr::Product(vec![]).canonical(), Canonicalized::new(Expr::Scalar(1.0)));
+    }
+
+    #[test]
+    fn canonical_flattens_nested_sums() {
+        let a = Arc::new(Expr::Symbol(Symbol::new("a")));
+        let b = Arc::new(Expr::Symbol(Symbol::new("b")));
+        let c = Arc::new(Expr::Symbol(Symbol::new("c")));
+        let nested = Expr::Sum(vec![Arc::new(Expr::Sum(vec![a.clone(), b.clone()])), c.clone()]);
+        let Expr::Sum(terms) = nested.canonical().into_inner() else {
+            panic!("expected Sum");
+        };
+        assert_eq!(terms.len(), 3);
+        assert!(terms.contains(&a));
+        assert!(terms.contains(&b));
+        assert!(terms.contains(&c));
+    }
+
+    #[test]
+    fn canonical_is_idempotent_on_a_deeply_nested_expr() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let theta = Arc::new(Expr::Symbol(Symbol::new("theta")));
+        let product = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), x0]));
+        let inner_sum = Arc::new(Expr::Sum(vec![z1, Arc::new(Expr::Scalar(3.0))]));
+        let nested = Arc::new(Expr::Sum(vec![product, inner_sum, theta]));
+        let deeply_nested = Expr::Sum(vec![nested.clone(), Arc::new(Expr::Sum(vec![nested]))]);
+
+        let once = deeply_nested.canonical();
+        let twice = once.clone().into_inner().canonical();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn unequal_hashes_short_circuit_and_equal_values_still_compare_equal() {
+        let a = Expr::Scalar(1.0).canonical();
+        let b = Expr::Scalar(2.0).canonical();
+        let c = Expr::Scalar(1.0).canonical();
+
+        assert_ne!(a.hash, b.hash, "distinct scalars should usually hash differently");
+        assert_ne!(a, b);
+
+        assert_eq!(a.hash, c.hash);
+        assert_eq!(a, c);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_and_sequential_canonical_forms_match_on_a_large_sum() {
+        // A deterministic but unsorted mix of scalars, symbols, and Pauli
+        // strings, well above `PARALLEL_THRESHOLD` so the parallel path
+        // actually forks.
+        let n = 500;
+        let terms: Vec<Arc<Expr>> = (0..n)
+            .map(|i| {
+                // A fixed pseudo-random permutation (multiply-by-odd mod n)
+                // so the input isn't already sorted.
+                let shuffled = (i * 257 + 13) % n;
+                match shuffled % 3 {
+                    0 => Arc::new(Expr::Scalar(shuffled as f64)),
+                    1 => Arc::new(Expr::Symbol(Symbol::new(&format!("x{shuffled}")))),
+                    _ => Arc::new(Expr::Pauli(PauliString::new([(shuffled, Pauli::X)]))),
+                }
+            })
+            .collect();
+        assert!(terms.len() > PARALLEL_THRESHOLD);
+
+        let sequential = canonicalize_children_sequential(&terms);
+        let parallel = canonicalize_children_parallel(&terms);
+        assert_eq!(sequential, parallel);
+    }
+}
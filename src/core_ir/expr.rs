@@ -0,0 +1,2769 @@
+//! The symbolic operator expression tree.
+//!
+//! An `Expr` is built from scalars, symbols, and Pauli strings combined
+//! with sums and products. Binding a symbol only swaps `Symbol::Named` for
+//! `Symbol::Bound` in place — it never evaluates anything, so the tree
+//! stays symbolic until something explicitly asks for a number.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::sync::Arc;
+
+use crate::error::CationError;
+
+use super::pauli::Pauli;
+use super::pauli_string::{DenseMatrix, PauliString};
+use super::phase::Phase;
+use super::symbol::Symbol;
+
+/// An OpenFermion-style `QubitOperator` term: the non-identity `(qubit,
+/// operator-letter)` pairs together with a real coefficient.
+pub type OpenFermionTerm = (Vec<(usize, char)>, f64);
+
+/// A Qiskit-style dense Pauli label paired with a complex coefficient.
+pub type QiskitTerm = (String, (f64, f64));
+
+/// A node in the symbolic expression tree.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expr {
+    Scalar(f64),
+    Symbol(Symbol),
+    Pauli(PauliString),
+    /// An empty `Sum` is constructible but has no defined value — this
+    /// crate does not treat it as `0` the way the mathematical convention
+    /// would. Prefer [`Expr::try_sum`] over constructing this variant
+    /// directly when `terms` could be empty.
+    Sum(Vec<Arc<Expr>>),
+    /// An empty `Product` is constructible but has no defined value — this
+    /// crate does not treat it as `1` the way the mathematical convention
+    /// would. Prefer [`Expr::try_product`] over constructing this variant
+    /// directly when `factors` could be empty.
+    Product(Vec<Arc<Expr>>),
+    Complex(f64, f64),
+}
+
+// `f64` has no total order (`NaN`), so we can't derive `Eq`/`Ord`. We
+// implement them by hand the same way `Symbol` does: structural equality
+// via `PartialEq`, and a deterministic total order via `total_cmp` on the
+// scalar leaves. This trades strict mathematical soundness (a `NaN` scalar
+// is not reflexively equal to itself under IEEE 754) for the ability to use
+// `Expr` as a sort key and `HashMap`/`HashSet` key, which canonicalization
+// needs.
+impl Eq for Expr {}
+
+impl PartialOrd for Expr {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Expr {
+    /// A deterministic total order: `Scalar < Symbol < Pauli < Sum < Product`,
+    /// tie-broken within each variant by content (`f64` via `total_cmp`, so
+    /// `NaN` has a defined position instead of comparing unordered).
+    ///
+    /// This is a purely **structural** order, not a semantic one: `Sum` and
+    /// `Product` compare their `Vec<Arc<Expr>>` contents lexicographically
+    /// (term-by-term, shorter-is-less-if-a-prefix), so `Sum([a, b])` and
+    /// `Sum([b, a])` generally compare unequal even though addition is
+    /// commutative and they represent the same operator. Don't rely on this
+    /// `Ord`/`PartialOrd` impl (or on putting raw `Expr`s in a sorted
+    /// container) for "are these the same sum up to reordering" — sort or
+    /// compare [`Expr::canonical`]'s output instead, which flattens and
+    /// sorts `Sum`/`Product` children first and is exactly the semantic
+    /// order this type doesn't otherwise provide.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        fn rank(e: &Expr) -> u8 {
+            match e {
+                Expr::Scalar(_) => 0,
+                Expr::Symbol(_) => 1,
+                Expr::Pauli(_) => 2,
+                Expr::Sum(_) => 3,
+                Expr::Product(_) => 4,
+                Expr::Complex(_, _) => 5,
+            }
+        }
+
+        match (self, other) {
+            (Expr::Scalar(a), Expr::Scalar(b)) => a.total_cmp(b),
+            (Expr::Symbol(a), Expr::Symbol(b)) => a.cmp(b),
+            (Expr::Pauli(a), Expr::Pauli(b)) => a.cmp(b),
+            (Expr::Sum(a), Expr::Sum(b)) => a.cmp(b),
+            (Expr::Product(a), Expr::Product(b)) => a.cmp(b),
+            (Expr::Complex(a_re, a_im), Expr::Complex(b_re, b_im)) => {
+                a_re.total_cmp(b_re).then_with(|| a_im.total_cmp(b_im))
+            }
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl std::hash::Hash for Expr {
+    /// Consistent with `PartialEq`/`Ord`: scalars hash via `to_bits` so
+    /// bit-identical `f64`s (including `NaN`) hash the same way they compare.
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        match self {
+            Expr::Scalar(v) => {
+                0u8.hash(state);
+                v.to_bits().hash(state);
+            }
+            Expr::Symbol(s) => {
+                1u8.hash(state);
+                s.hash(state);
+            }
+            Expr::Pauli(p) => {
+                2u8.hash(state);
+                p.hash(state);
+            }
+            Expr::Sum(terms) => {
+                3u8.hash(state);
+                terms.hash(state);
+            }
+            Expr::Product(factors) => {
+                4u8.hash(state);
+                factors.hash(state);
+            }
+            Expr::Complex(re, im) => {
+                5u8.hash(state);
+                re.to_bits().hash(state);
+                im.to_bits().hash(state);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    /// Renders infix syntax parseable by [`Expr::parse`]: `Sum` terms joined
+    /// by `" + "`, `Product` factors joined by `" * "`, with parentheses
+    /// added around a `Sum` factor inside a `Product` since `*` binds
+    /// tighter than `+`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Scalar(v) => write!(f, "{v}"),
+            Expr::Symbol(s) => write!(f, "{s}"),
+            Expr::Pauli(p) => write!(f, "{p}"),
+            Expr::Complex(re, im) => {
+                if *im < 0.0 {
+                    write!(f, "({re}-{}i)", -im)
+                } else {
+                    write!(f, "({re}+{im}i)")
+                }
+            }
+            Expr::Sum(terms) => {
+                let rendered: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+                write!(f, "{}", rendered.join(" + "))
+            }
+            Expr::Product(factors) => {
+                let rendered: Vec<String> = factors
+                    .iter()
+                    .map(|factor| match factor.as_ref() {
+                        Expr::Sum(_) => format!("({factor})"),
+                        _ => factor.to_string(),
+                    })
+                    .collect();
+                write!(f, "{}", rendered.join(" * "))
+            }
+        }
+    }
+}
+
+impl Expr {
+    /// Parses infix syntax like `2.0 * X0 + theta * (Z1 + Z2)` into an
+    /// `Expr` tree: `+` and `*` with the usual precedence and
+    /// left-associativity, parentheses, numeric literals, sparse Pauli
+    /// tokens (`X0`, `Z12`, ...), and bare identifiers as named symbols.
+    /// The inverse of [`Expr::to_string`] (via its `Display` impl) for
+    /// expressions built only from those constructs.
+    pub fn parse(input: &str) -> Result<Arc<Expr>, CationError> {
+        super::parser::parse(input)
+    }
+
+    /// The additive identity, `0`.
+    pub fn zero() -> Arc<Expr> {
+        Arc::new(Expr::Scalar(0.0))
+    }
+
+    /// The multiplicative identity, `1`.
+    pub fn one() -> Arc<Expr> {
+        Arc::new(Expr::Scalar(1.0))
+    }
+
+    /// Builds a `Sum` node over `terms`, requiring at least one term.
+    /// `Expr::Sum(vec![])` is constructible directly (the variant is
+    /// public) but has no well-defined value here — this crate doesn't
+    /// fold an empty sum to `0` the way the mathematical convention does —
+    /// so prefer this over `Expr::Sum` directly when `terms` might be empty.
+    pub fn try_sum(terms: Vec<Arc<Expr>>) -> Result<Arc<Expr>, CationError> {
+        if terms.is_empty() {
+            return Err(CationError::Other(
+                "Expr::try_sum requires at least one term, got an empty Vec".to_string(),
+            ));
+        }
+        Ok(Arc::new(Expr::Sum(terms)))
+    }
+
+    /// Builds a `Product` node over `factors`, requiring at least one
+    /// factor, for the same reason as [`Expr::try_sum`]: `Expr::Product(vec![])`
+    /// is constructible but meaningless (this crate doesn't fold it to `1`).
+    pub fn try_product(factors: Vec<Arc<Expr>>) -> Result<Arc<Expr>, CationError> {
+        if factors.is_empty() {
+            return Err(CationError::Other(
+                "Expr::try_product requires at least one factor, got an empty Vec".to_string(),
+            ));
+        }
+        Ok(Arc::new(Expr::Product(factors)))
+    }
+
+    /// Builds a `Scalar` leaf, rejecting non-finite values (`NaN` or
+    /// infinite). Canonicalization's `total_cmp`-based ordering gives `NaN`
+    /// a defined (if arbitrary) sort position, but a `NaN` scalar is almost
+    /// always an upstream bug, so callers that can validate up front should
+    /// prefer this over `Expr::Scalar` directly.
+    pub fn scalar(value: f64) -> Result<Arc<Expr>, CationError> {
+        if !value.is_finite() {
+            return Err(CationError::Other(format!("scalar must be finite, got {value}")));
+        }
+        Ok(Arc::new(Expr::Scalar(value)))
+    }
+
+    /// Builds a `Complex` leaf representing `re + im*i`, rejecting
+    /// non-finite components the same way [`Expr::scalar`] does.
+    /// Collapses to a real [`Expr::Scalar`] when `im` is exactly `0.0`, so
+    /// a real-valued coefficient never pays for the extra variant.
+    pub fn complex(re: f64, im: f64) -> Result<Arc<Expr>, CationError> {
+        if !re.is_finite() || !im.is_finite() {
+            return Err(CationError::Other(format!(
+                "complex scalar must be finite, got {re}+{im}i"
+            )));
+        }
+        if im == 0.0 {
+            return Ok(Arc::new(Expr::Scalar(re)));
+        }
+        Ok(Arc::new(Expr::Complex(re, im)))
+    }
+
+    /// The imaginary unit, `i`.
+    pub fn i() -> Arc<Expr> {
+        Arc::new(Expr::Complex(0.0, 1.0))
+    }
+
+    /// Replaces every `Symbol::Named(name)` leaf with `Symbol::Bound { name, value }`,
+    /// leaving every other node structurally identical. This never evaluates
+    /// anything; binding retains symbolic identity.
+    pub fn bind(&self, name: &str, value: f64) -> Arc<Expr> {
+        match self {
+            Expr::Symbol(Symbol::Named(n)) if n.as_ref() == name => {
+                Arc::new(Expr::Symbol(Symbol::Named(n.clone()).bind(value)))
+            }
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => {
+                Arc::new(self.clone())
+            }
+            Expr::Sum(terms) => Arc::new(Expr::Sum(
+                terms.iter().map(|t| t.bind(name, value)).collect(),
+            )),
+            Expr::Product(factors) => Arc::new(Expr::Product(
+                factors.iter().map(|f| f.bind(name, value)).collect(),
+            )),
+        }
+    }
+
+    /// Binds every `(name, value)` pair in `map`, one symbol at a time.
+    pub fn bind_all(&self, map: &HashMap<String, f64>) -> Arc<Expr> {
+        let mut result = Arc::new(self.clone());
+        for (name, &value) in map {
+            result = result.bind(name, value);
+        }
+        result
+    }
+
+    /// Binds `names[i]` to `values[i]` for each `i`, as repeated
+    /// [`Expr::bind`] calls. This is the glue between a flat parameter
+    /// vector from a gradient-based optimizer and this symbolic
+    /// expression; errors if `names` and `values` have different lengths.
+    pub fn bind_vector(&self, names: &[&str], values: &[f64]) -> Result<Arc<Expr>, CationError> {
+        if names.len() != values.len() {
+            return Err(CationError::DimensionMismatch(format!(
+                "bind_vector got {} names but {} values",
+                names.len(),
+                values.len()
+            )));
+        }
+        let mut result = Arc::new(self.clone());
+        for (&name, &value) in names.iter().zip(values) {
+            result = result.bind(name, value);
+        }
+        Ok(result)
+    }
+
+    /// Replaces every `Symbol::Named(name)` leaf with `replacement`,
+    /// rebuilding sums and products around it. Unlike [`Expr::bind`] the
+    /// replacement can be any subexpression, enabling change-of-variables.
+    pub fn substitute(&self, name: &str, replacement: &Arc<Expr>) -> Arc<Expr> {
+        match self {
+            Expr::Symbol(Symbol::Named(n)) if n.as_ref() == name => replacement.clone(),
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => {
+                Arc::new(self.clone())
+            }
+            Expr::Sum(terms) => Arc::new(Expr::Sum(
+                terms.iter().map(|t| t.substitute(name, replacement)).collect(),
+            )),
+            Expr::Product(factors) => Arc::new(Expr::Product(
+                factors
+                    .iter()
+                    .map(|f| f.substitute(name, replacement))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Symbolic derivative with respect to the named symbol, via the sum
+    /// and product rules: `d(Scalar)/d. = 0`, `d(Symbol name)/d. = 1` for
+    /// the matching name (`0` for every other symbol, bound or not), and
+    /// `Pauli` leaves are constants since only the coefficient structure
+    /// is being differentiated, not the operators themselves. The result
+    /// is generally not in lowest terms — run it through [`Expr::simplify`]
+    /// to fold away the `0`/`1` factors the product rule introduces and
+    /// merge repeated terms like `theta + theta` into `2 * theta`.
+    pub fn diff(&self, name: &str) -> Arc<Expr> {
+        match self {
+            Expr::Scalar(_) | Expr::Pauli(_) | Expr::Complex(_, _) => Arc::new(Expr::Scalar(0.0)),
+            Expr::Symbol(Symbol::Named(n)) if n.as_ref() == name => Arc::new(Expr::Scalar(1.0)),
+            Expr::Symbol(_) => Arc::new(Expr::Scalar(0.0)),
+            Expr::Sum(terms) => Arc::new(Expr::Sum(terms.iter().map(|t| t.diff(name)).collect())),
+            Expr::Product(factors) => Arc::new(Expr::Sum(
+                (0..factors.len())
+                    .map(|i| {
+                        let term = factors
+                            .iter()
+                            .enumerate()
+                            .map(|(j, f)| if i == j { f.diff(name) } else { f.clone() })
+                            .collect();
+                        Arc::new(Expr::Product(term))
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Collapses an expression containing only scalars and bound symbols
+    /// to a single number. Errors on any unbound `Symbol::Named` leaf or
+    /// any `Pauli` leaf, since neither has a numeric value on its own.
+    pub fn try_eval(&self) -> Result<f64, CationError> {
+        match self {
+            Expr::Scalar(v) => Ok(*v),
+            Expr::Symbol(Symbol::Bound { value, .. }) => Ok(*value),
+            Expr::Symbol(Symbol::BoundComplex { re, im, .. }) if *im == 0.0 => Ok(*re),
+            Expr::Symbol(Symbol::BoundComplex { name, re, im }) => Err(CationError::Other(format!(
+                "cannot evaluate non-real complex-bound symbol {name:?} ({re}+{im}i) to a real number"
+            ))),
+            Expr::Symbol(Symbol::Named(name)) => {
+                Err(CationError::UnboundSymbol(name.to_string()))
+            }
+            Expr::Pauli(_) => Err(CationError::Other("cannot evaluate a Pauli operator to a number".to_string())),
+            Expr::Complex(re, im) if *im == 0.0 => Ok(*re),
+            Expr::Complex(re, im) => Err(CationError::Other(format!(
+                "cannot evaluate non-real complex scalar {re}+{im}i to a real number"
+            ))),
+            Expr::Sum(terms) => terms.iter().try_fold(0.0, |acc, t| Ok(acc + t.try_eval()?)),
+            Expr::Product(factors) => {
+                factors.iter().try_fold(1.0, |acc, f| Ok(acc * f.try_eval()?))
+            }
+        }
+    }
+
+    /// Evaluates every fully-numeric subtree (built only from `Scalar`,
+    /// `Complex`, and bound `Symbol`s — no `Pauli` leaves and no free
+    /// `Symbol::Named`) down to a single `Scalar`/`Complex` leaf, and
+    /// recurses into the rest unchanged. Unlike [`Expr::try_eval`], which
+    /// fails outright on any Pauli or free symbol anywhere in the tree,
+    /// this reduces what it can and leaves the rest symbolic — e.g.
+    /// `(bound(theta, 2.0) + 1) * X0` becomes `3 * X0`.
+    pub fn partial_eval(&self) -> Arc<Expr> {
+        match self {
+            Expr::Scalar(_) | Expr::Complex(_, _) | Expr::Symbol(Symbol::Named(_)) | Expr::Pauli(_) => {
+                Arc::new(self.clone())
+            }
+            Expr::Symbol(Symbol::Bound { value, .. }) => Arc::new(Expr::Scalar(*value)),
+            Expr::Symbol(Symbol::BoundComplex { re, im, .. }) => Arc::new(complex_or_scalar(*re, *im)),
+            Expr::Sum(terms) => {
+                let evaluated: Vec<Arc<Expr>> = terms.iter().map(|t| t.partial_eval()).collect();
+                match fold_numeric(&evaluated, |acc, v| (acc.0 + v.0, acc.1 + v.1), (0.0, 0.0)) {
+                    Some((re, im)) => Arc::new(complex_or_scalar(re, im)),
+                    None => Arc::new(Expr::Sum(evaluated)),
+                }
+            }
+            Expr::Product(factors) => {
+                let evaluated: Vec<Arc<Expr>> = factors.iter().map(|f| f.partial_eval()).collect();
+                match fold_numeric(
+                    &evaluated,
+                    |acc, v| (acc.0 * v.0 - acc.1 * v.1, acc.0 * v.1 + acc.1 * v.0),
+                    (1.0, 0.0),
+                ) {
+                    Some((re, im)) => Arc::new(complex_or_scalar(re, im)),
+                    None => Arc::new(Expr::Product(evaluated)),
+                }
+            }
+        }
+    }
+
+    /// Flattens nested sums/products and folds constant `Scalar` terms,
+    /// dropping additive `0.0` and multiplicative `1.0`, and collapsing a
+    /// product containing `0.0` straight to `Scalar(0.0)`. Also merges
+    /// additive terms that share the same non-scalar remainder (via
+    /// [`Expr::split_coefficient`]), summing their coefficients and
+    /// dropping any group whose total is zero — so `X0 - X0` folds all the
+    /// way to `Scalar(0.0)`, not just to a `Product([Scalar(0.0), X0])`
+    /// sitting unevaluated in the tree. Unlike [`Expr::canonical_algebraic`],
+    /// this merge preserves each surviving term's first-seen order instead
+    /// of sorting, since `simplify` otherwise never reorders a `Sum`.
+    pub fn simplify(&self) -> Arc<Expr> {
+        match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => {
+                Arc::new(self.clone())
+            }
+            Expr::Sum(terms) => {
+                let mut flat = Vec::new();
+                let mut re_acc = 0.0;
+                let mut im_acc = 0.0;
+                for term in terms {
+                    match term.simplify().as_ref() {
+                        Expr::Sum(inner) => flat.extend(inner.iter().cloned()),
+                        Expr::Scalar(v) => re_acc += v,
+                        Expr::Complex(re, im) => {
+                            re_acc += re;
+                            im_acc += im;
+                        }
+                        _ => flat.push(term.simplify()),
+                    }
+                }
+
+                let mut order: Vec<Arc<Expr>> = Vec::new();
+                let mut grouped: HashMap<Arc<Expr>, (f64, f64)> = HashMap::new();
+                for term in flat {
+                    let ((re, im), remainder) = term.split_coefficient();
+                    grouped
+                        .entry(remainder.clone())
+                        .and_modify(|(r, i)| {
+                            *r += re;
+                            *i += im;
+                        })
+                        .or_insert_with(|| {
+                            order.push(remainder);
+                            (re, im)
+                        });
+                }
+                let mut flat: Vec<Arc<Expr>> = order
+                    .into_iter()
+                    .filter_map(|remainder| {
+                        let (re, im) = grouped[&remainder];
+                        if re == 0.0 && im == 0.0 {
+                            return None;
+                        }
+                        if im == 0.0 && re == 1.0 {
+                            return Some(remainder);
+                        }
+                        let coeff = if im == 0.0 { Expr::Scalar(re) } else { Expr::Complex(re, im) };
+                        Some(Arc::new(Expr::Product(vec![Arc::new(coeff), remainder])))
+                    })
+                    .collect();
+
+                if re_acc != 0.0 || im_acc != 0.0 || flat.is_empty() {
+                    let folded = if im_acc == 0.0 { Expr::Scalar(re_acc) } else { Expr::Complex(re_acc, im_acc) };
+                    flat.insert(0, Arc::new(folded));
+                }
+                if flat.len() == 1 {
+                    flat.into_iter().next().unwrap()
+                } else {
+                    Arc::new(Expr::Sum(flat))
+                }
+            }
+            Expr::Product(factors) => {
+                let mut flat = Vec::new();
+                let mut re_acc = 1.0;
+                let mut im_acc = 0.0;
+                for factor in factors {
+                    match factor.simplify().as_ref() {
+                        Expr::Product(inner) => flat.extend(inner.iter().cloned()),
+                        Expr::Scalar(v) => {
+                            re_acc *= v;
+                            im_acc *= v;
+                        }
+                        Expr::Complex(re, im) => {
+                            let new_re = re_acc * re - im_acc * im;
+                            let new_im = re_acc * im + im_acc * re;
+                            re_acc = new_re;
+                            im_acc = new_im;
+                        }
+                        _ => flat.push(factor.simplify()),
+                    }
+                }
+                if re_acc == 0.0 && im_acc == 0.0 {
+                    return Arc::new(Expr::Scalar(0.0));
+                }
+                if re_acc != 1.0 || im_acc != 0.0 || flat.is_empty() {
+                    let folded = if im_acc == 0.0 { Expr::Scalar(re_acc) } else { Expr::Complex(re_acc, im_acc) };
+                    flat.insert(0, Arc::new(folded));
+                }
+                if flat.len() == 1 {
+                    flat.into_iter().next().unwrap()
+                } else {
+                    Arc::new(Expr::Product(flat))
+                }
+            }
+        }
+    }
+
+    /// Recursively distributes every `Product` containing a `Sum` factor
+    /// into a `Sum` of `Product`s, preserving factor order since products
+    /// don't commute. This is the key step before collecting Pauli terms.
+    pub fn expand(&self) -> Arc<Expr> {
+        match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => {
+                Arc::new(self.clone())
+            }
+            Expr::Sum(terms) => Arc::new(Expr::Sum(terms.iter().map(|t| t.expand()).collect())),
+            Expr::Product(factors) => {
+                let expanded: Vec<Arc<Expr>> = factors.iter().map(|f| f.expand()).collect();
+
+                let mut terms: Vec<Vec<Arc<Expr>>> = vec![Vec::new()];
+                for factor in expanded {
+                    if let Expr::Sum(inner) = factor.as_ref() {
+                        let mut next = Vec::with_capacity(terms.len() * inner.len());
+                        for prefix in &terms {
+                            for summand in inner {
+                                let mut extended = prefix.clone();
+                                extended.push(summand.clone());
+                                next.push(extended);
+                            }
+                        }
+                        terms = next;
+                    } else {
+                        for prefix in &mut terms {
+                            prefix.push(factor.clone());
+                        }
+                    }
+                }
+
+                if terms.len() == 1 {
+                    Arc::new(Expr::Product(terms.into_iter().next().unwrap()))
+                } else {
+                    Arc::new(Expr::Sum(
+                        terms.into_iter().map(|t| Arc::new(Expr::Product(t))).collect(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Expands and reduces this expression into a `SparsePauliOp`-style sum
+    /// of `(coefficient, PauliString)` terms, merging identical strings by
+    /// summing their coefficients. The result is always sorted by
+    /// `PauliString`'s total order, so two calls over equal operators
+    /// (built in any order) produce identical term orderings — useful for
+    /// snapshot testing and diffing. Errors if a term mixes in a factor
+    /// that can't be folded numerically (currently any unbound symbol).
+    pub fn to_pauli_sum(&self) -> Result<Vec<(f64, PauliString)>, CationError> {
+        let expanded = self.expand();
+        let terms: Vec<Arc<Expr>> = match expanded.as_ref() {
+            Expr::Sum(terms) => terms.clone(),
+            _ => vec![expanded.clone()],
+        };
+
+        let mut acc: BTreeMap<PauliString, f64> = BTreeMap::new();
+        for term in terms {
+            let factors: Vec<Arc<Expr>> = match term.as_ref() {
+                Expr::Product(factors) => factors.clone(),
+                _ => vec![term.clone()],
+            };
+
+            let mut coeff = 1.0;
+            let mut phase = Phase::One;
+            let mut pauli_acc = PauliString::identity();
+            for factor in factors {
+                match factor.as_ref() {
+                    Expr::Pauli(p) => {
+                        let (ph, product) = pauli_acc.multiply(p);
+                        phase = phase * ph;
+                        pauli_acc = product;
+                    }
+                    Expr::Scalar(v) => coeff *= v,
+                    Expr::Symbol(Symbol::Bound { value, .. }) => coeff *= value,
+                    Expr::Symbol(Symbol::BoundComplex { re, im, .. }) if *im == 0.0 => coeff *= re,
+                    Expr::Symbol(Symbol::BoundComplex { name, re, im }) => {
+                        return Err(CationError::Other(format!(
+                            "to_pauli_sum cannot fold non-real complex-bound symbol {name:?} ({re}+{im}i) into a real coefficient"
+                        )))
+                    }
+                    Expr::Symbol(Symbol::Named(name)) => {
+                        return Err(CationError::UnboundSymbol(name.to_string()))
+                    }
+                    Expr::Complex(re, im) if *im == 0.0 => coeff *= re,
+                    Expr::Complex(re, im) => {
+                        return Err(CationError::Other(format!(
+                            "to_pauli_sum cannot fold non-real complex coefficient {re}+{im}i into a real coefficient"
+                        )))
+                    }
+                    Expr::Sum(_) | Expr::Product(_) => {
+                        return Err(CationError::Other("to_pauli_sum expected a fully expanded term".to_string()))
+                    }
+                }
+            }
+            let (real, imag) = phase.to_complex();
+            if imag != 0.0 {
+                return Err(CationError::Other(format!(
+                    "to_pauli_sum cannot fold non-real phase {phase} accumulated from multiplying overlapping Pauli factors into a real coefficient"
+                )));
+            }
+            *acc.entry(pauli_acc).or_insert(0.0) += coeff * real;
+        }
+
+        Ok(acc.into_iter().map(|(pauli, coeff)| (coeff, pauli)).collect())
+    }
+
+    /// Checks whether this expression and `other` are the same operator up
+    /// to one overall scalar factor: both are reduced via
+    /// [`Expr::to_pauli_sum`], and if they carry the same set of
+    /// `PauliString`s each scaled by one consistent `ratio = self/other`,
+    /// that ratio is returned. Returns `None` if the two sums don't share
+    /// exactly the same strings, don't scale by a single consistent ratio,
+    /// or either side fails to reduce to a Pauli sum at all (e.g. an
+    /// unbound symbol). Terms with a zero coefficient are dropped from the
+    /// comparison on both sides first, since they don't affect the operator.
+    pub fn equal_up_to_scalar(&self, other: &Expr) -> Option<f64> {
+        let mut a = self.to_pauli_sum().ok()?;
+        let mut b = other.to_pauli_sum().ok()?;
+        a.retain(|&(coeff, _)| coeff != 0.0);
+        b.retain(|&(coeff, _)| coeff != 0.0);
+        if a.len() != b.len() {
+            return None;
+        }
+        if a.is_empty() {
+            return Some(1.0);
+        }
+
+        let mut ratio: Option<f64> = None;
+        for ((coeff_a, pauli_a), (coeff_b, pauli_b)) in a.iter().zip(b.iter()) {
+            if pauli_a != pauli_b {
+                return None;
+            }
+            let term_ratio = coeff_a / coeff_b;
+            match ratio {
+                None => ratio = Some(term_ratio),
+                Some(r) if (term_ratio - r).abs() <= 1e-9 => {}
+                Some(_) => return None,
+            }
+        }
+        ratio
+    }
+
+    /// The number of distinct non-identity Pauli strings this expression
+    /// reduces to, after [`Expr::to_pauli_sum`] combines like terms and
+    /// terms whose coefficient cancels to zero are dropped. Useful for
+    /// estimating measurement cost before grouping into commuting sets.
+    pub fn num_pauli_terms(&self) -> Result<usize, CationError> {
+        let terms = self.to_pauli_sum()?;
+        Ok(terms.iter().filter(|&&(coeff, _)| coeff != 0.0).count())
+    }
+
+    /// Compares this expression and `other` term-by-term after both are
+    /// reduced via [`Expr::to_pauli_sum`], returning one
+    /// `(PauliString, self's coefficient, other's coefficient)` entry for
+    /// every string whose coefficient disagrees between the two operands
+    /// (a string present in only one operand is reported with `0.0` on the
+    /// other side), sorted by `PauliString`. Returns an empty `Vec` if the
+    /// two operators are equal. Errors for the same reasons `to_pauli_sum`
+    /// does, e.g. an unbound symbolic coefficient, on either side.
+    pub fn diff_terms(&self, other: &Expr) -> Result<Vec<(PauliString, f64, f64)>, CationError> {
+        let a: BTreeMap<PauliString, f64> = self.to_pauli_sum()?.into_iter().map(|(c, p)| (p, c)).collect();
+        let b: BTreeMap<PauliString, f64> = other.to_pauli_sum()?.into_iter().map(|(c, p)| (p, c)).collect();
+
+        let mut strings: Vec<&PauliString> = a.keys().chain(b.keys()).collect();
+        strings.sort();
+        strings.dedup();
+
+        Ok(strings
+            .into_iter()
+            .filter_map(|pauli| {
+                let coeff_a = a.get(pauli).copied().unwrap_or(0.0);
+                let coeff_b = b.get(pauli).copied().unwrap_or(0.0);
+                (coeff_a != coeff_b).then(|| (pauli.clone(), coeff_a, coeff_b))
+            })
+            .collect())
+    }
+
+    /// Like [`Expr::to_pauli_sum`] but keeps each coefficient as an `Expr`
+    /// (the product of all non-Pauli factors in a term) instead of folding
+    /// it to a number, so symbolic coefficients such as `theta * X0` are
+    /// preserved. Identical Pauli strings have their coefficients summed
+    /// into a `Sum`.
+    pub fn to_symbolic_pauli_sum(&self) -> Result<Vec<(Arc<Expr>, PauliString)>, CationError> {
+        let expanded = self.expand();
+        let terms: Vec<Arc<Expr>> = match expanded.as_ref() {
+            Expr::Sum(terms) => terms.clone(),
+            _ => vec![expanded.clone()],
+        };
+
+        let mut acc: BTreeMap<PauliString, Vec<Arc<Expr>>> = BTreeMap::new();
+        for term in terms {
+            let factors: Vec<Arc<Expr>> = match term.as_ref() {
+                Expr::Product(factors) => factors.clone(),
+                _ => vec![term.clone()],
+            };
+
+            let mut coeff_factors = Vec::new();
+            let mut phase = Phase::One;
+            let mut pauli_acc = PauliString::identity();
+            for factor in factors {
+                match factor.as_ref() {
+                    Expr::Pauli(p) => {
+                        let (ph, product) = pauli_acc.multiply(p);
+                        phase = phase * ph;
+                        pauli_acc = product;
+                    }
+                    Expr::Sum(_) | Expr::Product(_) => {
+                        return Err(CationError::Other("to_symbolic_pauli_sum expected a fully expanded term".to_string()))
+                    }
+                    _ => coeff_factors.push(factor.clone()),
+                }
+            }
+            let (real, imag) = phase.to_complex();
+            if imag != 0.0 {
+                coeff_factors.push(Arc::new(Expr::Complex(real, imag)));
+            } else if real != 1.0 {
+                coeff_factors.push(Arc::new(Expr::Scalar(real)));
+            }
+            let coeff = match coeff_factors.len() {
+                0 => Arc::new(Expr::Scalar(1.0)),
+                1 => coeff_factors.into_iter().next().unwrap(),
+                _ => Arc::new(Expr::Product(coeff_factors)),
+            };
+            acc.entry(pauli_acc).or_default().push(coeff);
+        }
+
+        Ok(acc
+            .into_iter()
+            .map(|(pauli, coeffs)| {
+                let coeff = if coeffs.len() == 1 {
+                    coeffs.into_iter().next().unwrap()
+                } else {
+                    Arc::new(Expr::Sum(coeffs))
+                };
+                (coeff, pauli)
+            })
+            .collect())
+    }
+
+    /// First-order Trotterization of this Hamiltonian into the ordered
+    /// per-term data a circuit builder needs to lay out
+    /// `(prod_k exp(-i * coefficient_k/steps * P_k))^steps`, without ever
+    /// building an exponential or a matrix itself. Decomposes via
+    /// [`Expr::to_pauli_sum`], divides each coefficient by `steps`, and
+    /// repeats that whole ordered sequence `steps` times. Errors for the
+    /// same reasons as `to_pauli_sum` (e.g. an unbound symbol), or if
+    /// `steps` is `0`.
+    pub fn trotter_step(&self, steps: u32) -> Result<Vec<(f64, PauliString)>, CationError> {
+        if steps == 0 {
+            return Err(CationError::Other(
+                "trotter_step requires at least one step, got 0".to_string(),
+            ));
+        }
+        let terms = self.to_pauli_sum()?;
+        let steps_f = f64::from(steps);
+        let mut result = Vec::with_capacity(terms.len() * steps as usize);
+        for _ in 0..steps {
+            for (coeff, pauli) in &terms {
+                result.push((coeff / steps_f, pauli.clone()));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Builds a sum of Pauli terms from OpenFermion's `QubitOperator` term
+    /// format: each `(qubit, operator-letter)` list paired with a real
+    /// coefficient, with the empty list denoting the identity term. Each
+    /// term becomes `Scalar(coeff) * PauliString`.
+    pub fn from_openfermion_terms(terms: &[OpenFermionTerm]) -> Result<Arc<Expr>, CationError> {
+        let mut summands = Vec::with_capacity(terms.len());
+        for (ops, coeff) in terms {
+            let pauli_ops = ops
+                .iter()
+                .map(|&(qubit, letter)| Ok((qubit, Pauli::try_from(letter)?)))
+                .collect::<Result<Vec<_>, CationError>>()?;
+            let pauli = PauliString::try_new(pauli_ops)?;
+            summands.push(Arc::new(Expr::Product(vec![
+                Arc::new(Expr::Scalar(*coeff)),
+                Arc::new(Expr::Pauli(pauli)),
+            ])));
+        }
+        Ok(Arc::new(Expr::Sum(summands)))
+    }
+
+    /// Builds a sum of Pauli terms from a coefficient-by-label map, the
+    /// shape a serialized Hamiltonian most naturally round-trips through as
+    /// JSON (e.g. `{"X0 Z2": 0.5, "I": -1.0}`). Each label is parsed via
+    /// [`PauliString::from_string`]'s whitespace-separated `"{op}{qubit}"`
+    /// token grammar, with the literal string `"I"` accepted as the
+    /// identity term (which `from_string` can't otherwise parse, since it
+    /// has no qubit index). Errors on any unparseable label.
+    pub fn from_label_map(map: &HashMap<String, f64>) -> Result<Arc<Expr>, CationError> {
+        let mut summands = Vec::with_capacity(map.len());
+        for (label, &coeff) in map {
+            let pauli = if label.trim() == "I" {
+                PauliString::identity()
+            } else {
+                PauliString::from_string(label)?
+            };
+            summands.push(Arc::new(Expr::Product(vec![
+                Arc::new(Expr::Scalar(coeff)),
+                Arc::new(Expr::Pauli(pauli)),
+            ])));
+        }
+        Ok(Arc::new(Expr::Sum(summands)))
+    }
+
+    /// The inverse of [`Expr::from_label_map`]: runs [`Expr::to_pauli_sum`]
+    /// and keys each resulting coefficient by its `PauliString`'s `Display`
+    /// label, except the identity term, which is keyed as the bare `"I"`
+    /// (matching `from_label_map`'s special case, since `Display` itself
+    /// renders identity as `"I0"`). Errors for the same reasons
+    /// `to_pauli_sum` does, e.g. an unbound symbolic coefficient.
+    pub fn to_label_map(&self) -> Result<HashMap<String, f64>, CationError> {
+        let terms = self.to_pauli_sum()?;
+        Ok(terms
+            .into_iter()
+            .map(|(coeff, pauli)| {
+                let label = if pauli.weight() == 0 { "I".to_string() } else { pauli.to_string() };
+                (label, coeff)
+            })
+            .collect())
+    }
+
+    /// Greedily partitions this expression's Pauli sum into groups where
+    /// every pair of terms is qubit-wise commuting (same operator, or
+    /// identity, on every qubit where both act), so all terms in a group
+    /// share a single measurement basis. Terms are placed in the first
+    /// compatible group, in sum order. Errors on an unbound symbolic
+    /// coefficient (the same cases [`Expr::to_pauli_sum`] errors on).
+    pub fn qubit_wise_commuting_groups(&self) -> Result<Vec<Vec<(f64, PauliString)>>, CationError> {
+        let terms = self.to_pauli_sum()?;
+        let mut groups: Vec<Vec<(f64, PauliString)>> = Vec::new();
+        'terms: for (coeff, pauli) in terms {
+            for group in groups.iter_mut() {
+                if group.iter().all(|(_, p)| p.qubit_wise_commutes_with(&pauli)) {
+                    group.push((coeff, pauli));
+                    continue 'terms;
+                }
+            }
+            groups.push(vec![(coeff, pauli)]);
+        }
+        Ok(groups)
+    }
+
+    /// Like [`Expr::qubit_wise_commuting_groups`], but groups by the weaker
+    /// general commutation rule ([`PauliString::commutes_with`], the parity
+    /// of same-qubit disagreements) instead of requiring matching operators
+    /// on every shared qubit. This yields fewer, larger groups, at the cost
+    /// of needing a basis-change circuit per group rather than a single
+    /// measurement basis. Uses the same greedy (first-fit, in sum order)
+    /// strategy as `qubit_wise_commuting_groups` rather than an optimal
+    /// graph coloring. Errors on an unbound symbolic coefficient (the same
+    /// cases [`Expr::to_pauli_sum`] errors on).
+    pub fn commuting_groups(&self) -> Result<Vec<Vec<(f64, PauliString)>>, CationError> {
+        let terms = self.to_pauli_sum()?;
+        let mut groups: Vec<Vec<(f64, PauliString)>> = Vec::new();
+        'terms: for (coeff, pauli) in terms {
+            for group in groups.iter_mut() {
+                if group.iter().all(|(_, p)| p.commutes_with(&pauli)) {
+                    group.push((coeff, pauli));
+                    continue 'terms;
+                }
+            }
+            groups.push(vec![(coeff, pauli)]);
+        }
+        Ok(groups)
+    }
+
+    /// The Pauli 1-norm `Σ |coeff|` over this expression's Pauli sum, used
+    /// for Trotter error bounds. Errors on an unbound symbolic coefficient
+    /// (the same cases [`Expr::to_pauli_sum`] errors on).
+    pub fn pauli_one_norm(&self) -> Result<f64, CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        Ok(pauli_sum.into_iter().map(|(coeff, _)| coeff.abs()).sum())
+    }
+
+    /// The trace `Tr(H)` of this expression over `num_qubits` qubits. Every
+    /// non-identity Pauli string is traceless, so this reduces to
+    /// `2^num_qubits` times the coefficient of the identity term in the
+    /// Pauli sum — no matrix is built. Errors on an unbound symbolic
+    /// coefficient (the same cases [`Expr::to_pauli_sum`] errors on).
+    pub fn trace(&self, num_qubits: usize) -> Result<(f64, f64), CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        let identity_coeff: f64 = pauli_sum
+            .into_iter()
+            .filter(|(_, pauli)| pauli.is_identity())
+            .map(|(coeff, _)| coeff)
+            .sum();
+        let dim = (1usize << num_qubits) as f64;
+        Ok((identity_coeff * dim, 0.0))
+    }
+
+    /// The summed coefficient of every identity Pauli term in this
+    /// expression's Pauli sum, i.e. the constant energy offset separate from
+    /// the traceless part. Unlike [`Expr::trace`] this isn't scaled by
+    /// `2^num_qubits`, since it's meant to be added back to
+    /// [`Expr::traceless_part`]'s spectrum directly rather than compared
+    /// against a matrix trace. Errors on an unbound symbolic coefficient (the
+    /// same cases [`Expr::to_pauli_sum`] errors on).
+    pub fn identity_coefficient(&self) -> Result<f64, CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        Ok(pauli_sum
+            .into_iter()
+            .filter(|(_, pauli)| pauli.is_identity())
+            .map(|(coeff, _)| coeff)
+            .sum())
+    }
+
+    /// This expression with its identity term removed, leaving only the
+    /// traceless part. Pairs with [`Expr::identity_coefficient`]: summing the
+    /// two recovers the original spectrum, with the constant shift isolated
+    /// for algorithms (e.g. energy estimation) that need it separately.
+    /// Errors on an unbound symbolic coefficient (the same cases
+    /// [`Expr::to_pauli_sum`] errors on).
+    pub fn traceless_part(&self) -> Result<Arc<Expr>, CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        let terms: Vec<Arc<Expr>> = pauli_sum
+            .into_iter()
+            .filter(|(_, pauli)| !pauli.is_identity())
+            .map(|(coeff, pauli)| {
+                Arc::new(Expr::Product(vec![
+                    Arc::new(Expr::Scalar(coeff)),
+                    Arc::new(Expr::Pauli(pauli)),
+                ]))
+            })
+            .collect();
+        Ok(match terms.len() {
+            0 => Expr::zero(),
+            1 => terms.into_iter().next().unwrap(),
+            _ => Arc::new(Expr::Sum(terms)),
+        })
+    }
+
+    /// The full `2^num_qubits x 2^num_qubits` dense matrix for this
+    /// expression: collects the Pauli sum and accumulates each term's
+    /// coefficient-weighted [`PauliString::to_dense_matrix`]. Errors on an
+    /// unbound symbolic coefficient (the same cases [`Expr::to_pauli_sum`]
+    /// errors on).
+    pub fn to_dense_matrix(&self, num_qubits: usize) -> Result<DenseMatrix, CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        let dim = 1usize << num_qubits;
+        let mut matrix = vec![vec![(0.0, 0.0); dim]; dim];
+        for (coeff, pauli) in pauli_sum {
+            let term = pauli.to_dense_matrix(num_qubits);
+            for (row, term_row) in term.iter().enumerate() {
+                for (col, &(real, imag)) in term_row.iter().enumerate() {
+                    matrix[row][col].0 += coeff * real;
+                    matrix[row][col].1 += coeff * imag;
+                }
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// The expectation value `⟨ψ|H|ψ⟩` of this expression treated as a
+    /// Hamiltonian, over a complex statevector `state` of length
+    /// `2^num_qubits`. Collects the Pauli sum and weights each term's
+    /// [`PauliString::expectation`] by its coefficient. Errors on an
+    /// unbound symbolic coefficient (the same cases [`Expr::to_pauli_sum`]
+    /// errors on) or a mismatched state length.
+    pub fn expectation(&self, state: &[(f64, f64)], num_qubits: usize) -> Result<(f64, f64), CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        let mut total = (0.0, 0.0);
+        for (coeff, pauli) in pauli_sum {
+            let (real, imag) = pauli.expectation(state, num_qubits)?;
+            total = (total.0 + coeff * real, total.1 + coeff * imag);
+        }
+        Ok(total)
+    }
+
+    /// The inverse of [`Expr::from_openfermion_terms`]: runs [`Expr::to_pauli_sum`]
+    /// and formats each `(coefficient, PauliString)` pair as OpenFermion
+    /// expects, with the identity term as an empty index list. Errors if any
+    /// coefficient is still symbolic (the same cases `to_pauli_sum` errors on).
+    pub fn to_openfermion_terms(&self) -> Result<Vec<OpenFermionTerm>, CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        Ok(pauli_sum
+            .into_iter()
+            .map(|(coeff, pauli)| {
+                let ops = pauli
+                    .iter()
+                    .map(|(qubit, op)| {
+                        let letter = match op {
+                            Pauli::I => 'I',
+                            Pauli::X => 'X',
+                            Pauli::Y => 'Y',
+                            Pauli::Z => 'Z',
+                        };
+                        (qubit, letter)
+                    })
+                    .collect();
+                (ops, coeff)
+            })
+            .collect())
+    }
+
+    /// Collects the Pauli sum and renders each string as a Qiskit-style
+    /// dense label over `num_qubits`: a fixed-width string with one
+    /// character per qubit, unmentioned qubits filled with `I`, and qubit 0
+    /// as the rightmost character (Qiskit's convention, opposite of
+    /// [`PauliString::from_string`]'s sparse notation). The coefficient is
+    /// `(real, imaginary)` to accommodate future complex scalars; the
+    /// imaginary part is always `0.0` today. Errors on a symbolic
+    /// coefficient or a string whose support doesn't fit in `num_qubits`.
+    pub fn to_qiskit_labels(&self, num_qubits: usize) -> Result<Vec<QiskitTerm>, CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        pauli_sum
+            .into_iter()
+            .map(|(coeff, pauli)| {
+                if let Some(&max_qubit) = pauli.support().last() {
+                    if max_qubit >= num_qubits {
+                        return Err(CationError::DimensionMismatch(format!(
+                            "num_qubits {num_qubits} too small for support up to qubit {max_qubit}"
+                        )));
+                    }
+                }
+                let label: String = (0..num_qubits)
+                    .map(|position| pauli.get(num_qubits - 1 - position).to_string())
+                    .collect();
+                Ok((label, (coeff, 0.0)))
+            })
+            .collect()
+    }
+
+    /// Collects the Pauli sum and drops every term whose `|coeff| <
+    /// threshold`, rebuilding the expression from what's left (each
+    /// surviving term as `Scalar(coeff) * PauliString`, matching
+    /// [`Expr::from_openfermion_terms`]'s term shape). Useful for
+    /// approximating a Hamiltonian with many negligible terms. Returns the
+    /// truncated expression alongside the discarded 1-norm (`Σ |coeff|`
+    /// over dropped terms) so the caller can bound the resulting error.
+    /// Errors on an unresolved symbolic coefficient (the same cases
+    /// [`Expr::to_pauli_sum`] errors on).
+    pub fn truncate(&self, threshold: f64) -> Result<(Arc<Expr>, f64), CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+        let mut kept = Vec::new();
+        let mut discarded_norm = 0.0;
+        for (coeff, pauli) in pauli_sum {
+            if coeff.abs() < threshold {
+                discarded_norm += coeff.abs();
+            } else {
+                kept.push(Arc::new(Expr::Product(vec![
+                    Arc::new(Expr::Scalar(coeff)),
+                    Arc::new(Expr::Pauli(pauli)),
+                ])));
+            }
+        }
+        let truncated = match kept.len() {
+            0 => Expr::zero(),
+            1 => kept.into_iter().next().unwrap(),
+            _ => Arc::new(Expr::Sum(kept)),
+        };
+        Ok((truncated, discarded_norm))
+    }
+
+    /// Collects the Pauli sum and splits it into sub-expressions acting on
+    /// disjoint sets of qubits, revealing tensor-product (block-diagonal)
+    /// structure that can be simulated independently. Terms are grouped by
+    /// union-find over shared qubit support: two terms land in the same
+    /// component iff they share at least one qubit index, directly or
+    /// transitively through another term. Each returned sub-expression is a
+    /// `Sum` of `Scalar(coeff) * PauliString` terms (or a single such product
+    /// if its component has only one term), in the same relative order as
+    /// `to_pauli_sum`. Errors on an unresolved symbolic coefficient (the same
+    /// cases [`Expr::to_pauli_sum`] errors on).
+    pub fn split_by_support(&self) -> Result<Vec<Arc<Expr>>, CationError> {
+        let pauli_sum = self.to_pauli_sum()?;
+
+        let mut parent: Vec<usize> = (0..pauli_sum.len()).collect();
+        fn find(parent: &mut [usize], x: usize) -> usize {
+            if parent[x] != x {
+                parent[x] = find(parent, parent[x]);
+            }
+            parent[x]
+        }
+        fn union(parent: &mut [usize], a: usize, b: usize) {
+            let (ra, rb) = (find(parent, a), find(parent, b));
+            if ra != rb {
+                parent[ra] = rb;
+            }
+        }
+
+        let mut owner: HashMap<usize, usize> = HashMap::new();
+        for (i, (_, pauli)) in pauli_sum.iter().enumerate() {
+            for qubit in pauli.support() {
+                if let Some(&j) = owner.get(&qubit) {
+                    union(&mut parent, i, j);
+                } else {
+                    owner.insert(qubit, i);
+                }
+            }
+        }
+
+        let mut groups: BTreeMap<usize, Vec<Arc<Expr>>> = BTreeMap::new();
+        for (i, (coeff, pauli)) in pauli_sum.into_iter().enumerate() {
+            let root = find(&mut parent, i);
+            let term = Arc::new(Expr::Product(vec![
+                Arc::new(Expr::Scalar(coeff)),
+                Arc::new(Expr::Pauli(pauli)),
+            ]));
+            groups.entry(root).or_default().push(term);
+        }
+
+        Ok(groups
+            .into_values()
+            .map(|mut terms| match terms.len() {
+                1 => terms.pop().unwrap(),
+                _ => Arc::new(Expr::Sum(terms)),
+            })
+            .collect())
+    }
+
+    /// Visits every node in pre-order (a node before its children), calling
+    /// `f` once per node. Underpins analyses like [`Expr::free_symbols`] and
+    /// [`Expr::num_qubits`] that only care about the leaves (`Scalar`,
+    /// `Symbol`, `Pauli`) but don't want to hand-roll traversal.
+    pub fn walk<F: FnMut(&Expr)>(&self, f: &mut F) {
+        f(self);
+        match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => {}
+            Expr::Sum(terms) => {
+                for term in terms {
+                    term.walk(f);
+                }
+            }
+            Expr::Product(factors) => {
+                for factor in factors {
+                    factor.walk(f);
+                }
+            }
+        }
+    }
+
+    /// Rebuilds this expression bottom-up: children are rewritten first,
+    /// then `f` is offered the resulting node, with `Some(replacement)`
+    /// substituting it and `None` keeping it as rebuilt. This is the
+    /// generic engine underneath substitution, simplification, and
+    /// user-defined passes.
+    pub fn map_bottom_up(&self, f: impl Fn(&Expr) -> Option<Arc<Expr>>) -> Arc<Expr> {
+        self.map_bottom_up_inner(&f)
+    }
+
+    fn map_bottom_up_inner(&self, f: &impl Fn(&Expr) -> Option<Arc<Expr>>) -> Arc<Expr> {
+        let rebuilt = match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => {
+                Arc::new(self.clone())
+            }
+            Expr::Sum(terms) => Arc::new(Expr::Sum(terms.iter().map(|t| t.map_bottom_up_inner(f)).collect())),
+            Expr::Product(factors) => {
+                Arc::new(Expr::Product(factors.iter().map(|factor| factor.map_bottom_up_inner(f)).collect()))
+            }
+        };
+        f(&rebuilt).unwrap_or(rebuilt)
+    }
+
+    /// Every `Symbol` leaf (`Named` or `Bound`) appearing in this
+    /// expression, in first-encountered order with duplicates removed.
+    pub fn free_symbols(&self) -> Vec<Symbol> {
+        let mut seen = HashSet::new();
+        let mut symbols = Vec::new();
+        self.collect_free_symbols(&mut seen, &mut symbols);
+        symbols
+    }
+
+    fn collect_free_symbols(&self, seen: &mut HashSet<Symbol>, out: &mut Vec<Symbol>) {
+        match self {
+            Expr::Symbol(sym) => {
+                if seen.insert(sym.clone()) {
+                    out.push(sym.clone());
+                }
+            }
+            Expr::Scalar(_) | Expr::Pauli(_) | Expr::Complex(_, _) => {}
+            Expr::Sum(terms) => {
+                for term in terms {
+                    term.collect_free_symbols(seen, out);
+                }
+            }
+            Expr::Product(factors) => {
+                for factor in factors {
+                    factor.collect_free_symbols(seen, out);
+                }
+            }
+        }
+    }
+
+    /// The Hermitian conjugate (adjoint) of this expression. Real scalars
+    /// and Pauli operators are unchanged (Paulis are Hermitian); a
+    /// `Complex` scalar has its imaginary part negated, e.g.
+    /// `dagger(i*X0) == -i*X0`, as does a `Symbol::BoundComplex` (a
+    /// genuinely complex bound parameter); other symbols are unchanged;
+    /// sums conjugate term-by-term; products reverse factor order since
+    /// operators don't commute, e.g. `dagger(X0 * Y1) == Y1 * X0`.
+    pub fn dagger(&self) -> Arc<Expr> {
+        match self {
+            Expr::Scalar(_) | Expr::Pauli(_) => Arc::new(self.clone()),
+            Expr::Symbol(Symbol::BoundComplex { name, re, im }) => Arc::new(Expr::Symbol(
+                Symbol::BoundComplex {
+                    name: name.clone(),
+                    re: *re,
+                    im: -im,
+                },
+            )),
+            Expr::Symbol(_) => Arc::new(self.clone()),
+            Expr::Complex(re, im) => Arc::new(Expr::Complex(*re, -im)),
+            Expr::Sum(terms) => Arc::new(Expr::Sum(terms.iter().map(|t| t.dagger()).collect())),
+            Expr::Product(factors) => Arc::new(Expr::Product(
+                factors.iter().rev().map(|f| f.dagger()).collect(),
+            )),
+        }
+    }
+
+    /// Splits this expression into a complex scalar coefficient `(re, im)`
+    /// and the product of its remaining, non-scalar factors. Every
+    /// `Scalar`/`Complex` factor of a `Product` is folded into the
+    /// coefficient, wherever it appears (not just a leading position), and
+    /// the product of what's left is returned; a non-`Product` expression
+    /// has coefficient `(1.0, 0.0)`, and a bare `Scalar`/`Complex` returns
+    /// its value paired with [`Expr::one`].
+    pub fn split_coefficient(&self) -> ((f64, f64), Arc<Expr>) {
+        match self {
+            Expr::Scalar(v) => ((*v, 0.0), Expr::one()),
+            Expr::Complex(re, im) => ((*re, *im), Expr::one()),
+            Expr::Product(factors) => {
+                let mut re_acc = 1.0;
+                let mut im_acc = 0.0;
+                let mut rest = Vec::with_capacity(factors.len());
+                for factor in factors {
+                    match factor.as_ref() {
+                        Expr::Scalar(v) => {
+                            re_acc *= v;
+                            im_acc *= v;
+                        }
+                        Expr::Complex(re, im) => {
+                            let new_re = re_acc * re - im_acc * im;
+                            let new_im = re_acc * im + im_acc * re;
+                            re_acc = new_re;
+                            im_acc = new_im;
+                        }
+                        _ => rest.push(factor.clone()),
+                    }
+                }
+                let remainder = match rest.len() {
+                    0 => Expr::one(),
+                    1 => rest.into_iter().next().unwrap(),
+                    _ => Arc::new(Expr::Product(rest)),
+                };
+                ((re_acc, im_acc), remainder)
+            }
+            _ => ((1.0, 0.0), Arc::new(self.clone())),
+        }
+    }
+
+    /// Builds `self` raised to the `n`-th power as a `Product` of `n`
+    /// copies of `self`, preserving factor order since products don't
+    /// commute; `pow(0)` is [`Expr::one`].
+    pub fn pow(&self, n: u32) -> Arc<Expr> {
+        if n == 0 {
+            return Expr::one();
+        }
+        let factor = Arc::new(self.clone());
+        Arc::new(Expr::Product(vec![factor; n as usize]))
+    }
+
+    /// The Kronecker/tensor product of two operators on separate registers:
+    /// `other`'s `Pauli` leaves are relabeled up by `shift` qubits (via
+    /// [`PauliString::relabel`]), then multiplied with `self`. This is the
+    /// operator-level analog of [`PauliString::tensor`]; since the two
+    /// operands act on disjoint qubits after shifting, they commute and the
+    /// factor order is just a convention (`self` first).
+    pub fn tensor(&self, other: &Arc<Expr>, shift: usize) -> Arc<Expr> {
+        let shifted = other.map_bottom_up(|node| match node {
+            Expr::Pauli(p) => Some(Arc::new(Expr::Pauli(
+                p.relabel(|qubit| qubit + shift)
+                    .expect("shifting every index by the same offset can't introduce a collision"),
+            ))),
+            _ => None,
+        });
+        Arc::new(Expr::Product(vec![Arc::new(self.clone()), shifted]))
+    }
+
+    /// Renders LaTeX math-mode markup: Pauli strings as `X_{0} Z_{1}` (the
+    /// identity as `I`), products joined by `\cdot`, sums by `+`, scalars by
+    /// their numeric value, and symbols by their name as-is (so a caller can
+    /// map `"theta"` to `\theta` themselves if they want Greek letters).
+    /// Parenthesizes a `Sum` nested inside a `Product`, matching [`Expr`]'s
+    /// `Display` impl.
+    pub fn to_latex(&self) -> String {
+        match self {
+            Expr::Scalar(v) => format!("{v}"),
+            Expr::Symbol(s) => s.to_string(),
+            Expr::Complex(re, im) => {
+                if *im < 0.0 {
+                    format!("{re}-{}i", -im)
+                } else {
+                    format!("{re}+{im}i")
+                }
+            }
+            Expr::Pauli(p) => {
+                if p.is_identity() {
+                    "I".to_string()
+                } else {
+                    p.iter()
+                        .map(|(qubit, op)| format!("{op}_{{{qubit}}}"))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }
+            }
+            Expr::Sum(terms) => terms.iter().map(|t| t.to_latex()).collect::<Vec<_>>().join(" + "),
+            Expr::Product(factors) => factors
+                .iter()
+                .map(|factor| match factor.as_ref() {
+                    Expr::Sum(_) => format!("({})", factor.to_latex()),
+                    _ => factor.to_latex(),
+                })
+                .collect::<Vec<_>>()
+                .join(r" \cdot "),
+        }
+    }
+
+    /// The register size implied by this expression: one more than the
+    /// highest qubit index appearing in any `Pauli` leaf, or `0` if there
+    /// are none. Used to pick the matrix dimension for
+    /// [`PauliString::to_dense_matrix`](super::pauli_string::PauliString::to_dense_matrix).
+    pub fn num_qubits(&self) -> usize {
+        match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Complex(_, _) => 0,
+            Expr::Pauli(p) => p.support().last().map_or(0, |&q| q + 1),
+            Expr::Sum(terms) => terms.iter().map(|t| t.num_qubits()).max().unwrap_or(0),
+            Expr::Product(factors) => factors.iter().map(|f| f.num_qubits()).max().unwrap_or(0),
+        }
+    }
+
+    /// The length of the longest root-to-leaf path, where a leaf (`Scalar`,
+    /// `Symbol`, `Pauli`) has depth `1`. Useful alongside [`Expr::size`] to
+    /// decide whether to canonicalize or simplify before a heavy operation.
+    pub fn depth(&self) -> usize {
+        match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => 1,
+            Expr::Sum(terms) => 1 + terms.iter().map(|t| t.depth()).max().unwrap_or(0),
+            Expr::Product(factors) => 1 + factors.iter().map(|f| f.depth()).max().unwrap_or(0),
+        }
+    }
+
+    /// The total number of nodes in this expression's tree, counting `self`.
+    pub fn size(&self) -> usize {
+        match self {
+            Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_) | Expr::Complex(_, _) => 1,
+            Expr::Sum(terms) => 1 + terms.iter().map(|t| t.size()).sum::<usize>(),
+            Expr::Product(factors) => 1 + factors.iter().map(|f| f.size()).sum::<usize>(),
+        }
+    }
+
+    /// Like structural canonicalization, but also merges identical summands
+    /// into a single scalar-weighted term (so `X0 + X0` becomes `2*X0`) and
+    /// multiplies adjacent `Pauli` factors within a product together. This
+    /// changes algebraic structure, unlike the purely reordering structural
+    /// `canonical` form, so the two are kept separate.
+    pub fn canonical_algebraic(&self) -> Arc<Expr> {
+        Arc::new(super::canonical::canonical_algebraic_inner(self))
+    }
+
+    /// Like structural canonicalization, but also sorts maximal runs of
+    /// pairwise-commuting factors within each product into `Ord` order
+    /// (using [`PauliString::commutes_with`]), leaving non-commuting
+    /// neighbors in place. So `X1 * X0` (disjoint qubits) canonicalizes the
+    /// same as `X0 * X1`, but `X0 * Z0` is left as-is since swapping them
+    /// would change the operator. Gated behind this separate method rather
+    /// than folded into `canonical` so existing callers are unaffected.
+    pub fn canonical_commuting_sorted(&self) -> Arc<Expr> {
+        Arc::new(super::canonical::canonical_commuting_sorted_inner(self))
+    }
+
+    /// Structural equality that tolerates small numeric drift in `Scalar`
+    /// leaves, within `tol` absolute or relative to the larger magnitude.
+    /// Sums compare their terms sorted by [`Ord`] (a lightweight canonical
+    /// form) rather than in original order, since term order isn't
+    /// meaningful for addition; products compare factor-by-factor in order,
+    /// since multiplication doesn't commute in general.
+    pub fn approx_eq(&self, other: &Expr, tol: f64) -> bool {
+        match (self, other) {
+            (Expr::Scalar(a), Expr::Scalar(b)) => {
+                let diff = (a - b).abs();
+                diff <= tol || diff <= tol * a.abs().max(b.abs())
+            }
+            (Expr::Symbol(a), Expr::Symbol(b)) => a == b,
+            (Expr::Pauli(a), Expr::Pauli(b)) => a == b,
+            (Expr::Complex(a_re, a_im), Expr::Complex(b_re, b_im)) => {
+                let diff_re = (a_re - b_re).abs();
+                let diff_im = (a_im - b_im).abs();
+                (diff_re <= tol || diff_re <= tol * a_re.abs().max(b_re.abs()))
+                    && (diff_im <= tol || diff_im <= tol * a_im.abs().max(b_im.abs()))
+            }
+            (Expr::Sum(a), Expr::Sum(b)) => {
+                if a.len() != b.len() {
+                    return false;
+                }
+                let mut a_sorted = a.clone();
+                let mut b_sorted = b.clone();
+                a_sorted.sort();
+                b_sorted.sort();
+                a_sorted
+                    .iter()
+                    .zip(b_sorted.iter())
+                    .all(|(x, y)| x.approx_eq(y, tol))
+            }
+            (Expr::Product(a), Expr::Product(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.approx_eq(y, tol))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this expression equals its own adjoint, `H == H†`.
+    ///
+    /// This compares a normalized form (`expand` then `simplify`) of
+    /// `self` against the same normalized form of `self.dagger()`, so it
+    /// won't catch every algebraic equality (e.g. it won't notice that two
+    /// differently-ordered commuting factors are equal). Once full
+    /// canonicalization lands this should use that instead for a stronger
+    /// guarantee.
+    pub fn is_hermitian(&self) -> bool {
+        let normalize = |e: &Expr| e.expand().simplify();
+        normalize(self) == normalize(&self.dagger())
+    }
+
+    /// Renders like [`Display`](fmt::Display), but rounds every `Scalar`
+    /// coefficient to `precision` decimal places, so printed Hamiltonians
+    /// don't drown in `f64` noise (`3.0000000000000004` becomes `3.00`). A
+    /// `Product` factor that is *exactly* `Expr::Scalar(1.0)` is dropped
+    /// (e.g. `1.0 * X0` becomes `X0`); this is a literal-value check done
+    /// before rounding, not a check on the rounded display, so a factor
+    /// merely close to `1.0` (like `0.9999999999999998`) still prints as
+    /// `1.00 * X0` rather than being elided. `Complex` leaves are left at
+    /// full precision, since `Display` already only uses them for
+    /// genuinely complex phases, not noisy floats.
+    pub fn display_with(&self, precision: usize) -> String {
+        match self {
+            Expr::Scalar(v) => format!("{v:.precision$}"),
+            Expr::Symbol(s) => s.to_string(),
+            Expr::Pauli(p) => p.to_string(),
+            Expr::Complex(re, im) => {
+                if *im < 0.0 {
+                    format!("({re}-{}i)", -im)
+                } else {
+                    format!("({re}+{im}i)")
+                }
+            }
+            Expr::Sum(terms) => terms
+                .iter()
+                .map(|t| t.display_with(precision))
+                .collect::<Vec<_>>()
+                .join(" + "),
+            Expr::Product(factors) => factors
+                .iter()
+                .filter(|factor| !matches!(factor.as_ref(), Expr::Scalar(v) if *v == 1.0))
+                .map(|factor| match factor.as_ref() {
+                    Expr::Sum(_) => format!("({})", factor.display_with(precision)),
+                    _ => factor.display_with(precision),
+                })
+                .collect::<Vec<_>>()
+                .join(" * "),
+        }
+    }
+}
+
+// `Arc<T>` is not a "fundamental" type in `std`, so Rust's orphan rules
+// forbid implementing a foreign trait like `Add` directly on `Arc<Expr>`
+// even though `Expr` is local. We implement on `&Expr` instead (references
+// are fundamental) and build the `Arc<Expr>` node inside; callers holding
+// an `Arc<Expr>` write `&*a + &*b` (or `a.as_ref() + b.as_ref()`).
+
+impl Add for &Expr {
+    type Output = Arc<Expr>;
+
+    fn add(self, rhs: &Expr) -> Arc<Expr> {
+        Arc::new(Expr::Sum(vec![Arc::new(self.clone()), Arc::new(rhs.clone())]))
+    }
+}
+
+impl Mul for &Expr {
+    type Output = Arc<Expr>;
+
+    fn mul(self, rhs: &Expr) -> Arc<Expr> {
+        Arc::new(Expr::Product(vec![Arc::new(self.clone()), Arc::new(rhs.clone())]))
+    }
+}
+
+impl Sub for &Expr {
+    type Output = Arc<Expr>;
+
+    /// `a - b` is built as `a + (-1.0) * b`.
+    fn sub(self, rhs: &Expr) -> Arc<Expr> {
+        let neg_rhs = Arc::new(Expr::Product(vec![
+            Arc::new(Expr::Scalar(-1.0)),
+            Arc::new(rhs.clone()),
+        ]));
+        Arc::new(Expr::Sum(vec![Arc::new(self.clone()), neg_rhs]))
+    }
+}
+
+// `Arc<T>` already has a blanket `impl<T> From<T> for Arc<T>` in `std`, so
+// converting a scalar/symbol/Pauli all the way into `Arc<Expr>` is a two-step
+// `Arc::new(value.into())` (or `Arc::from(Expr::from(value))`); Rust's
+// orphan rules forbid a direct `impl From<f64> for Arc<Expr>` since neither
+// `f64` nor `Arc` is a local type.
+
+impl From<f64> for Expr {
+    fn from(value: f64) -> Expr {
+        Expr::Scalar(value)
+    }
+}
+
+impl From<Symbol> for Expr {
+    fn from(symbol: Symbol) -> Expr {
+        Expr::Symbol(symbol)
+    }
+}
+
+impl From<PauliString> for Expr {
+    fn from(pauli: PauliString) -> Expr {
+        Expr::Pauli(pauli)
+    }
+}
+
+impl Neg for &Expr {
+    type Output = Arc<Expr>;
+
+    /// Negation is `(-1.0) * self`.
+    fn neg(self) -> Arc<Expr> {
+        Arc::new(Expr::Product(vec![
+            Arc::new(Expr::Scalar(-1.0)),
+            Arc::new(self.clone()),
+        ]))
+    }
+}
+
+/// A subtraction helper usable directly on `Arc<Expr>` values, since Rust's
+/// orphan rules forbid implementing `Sub` on `Arc<Expr>` itself (see the
+/// note above the `&Expr` operator impls).
+pub fn sub(a: &Arc<Expr>, b: &Arc<Expr>) -> Arc<Expr> {
+    a.as_ref() - b.as_ref()
+}
+
+/// Builds `Some(acc)` by folding `combine` over every node's `(re, im)`
+/// value, starting from `identity`, as long as every node is a
+/// `Scalar`/`Complex` leaf; `None` as soon as one isn't, short-circuiting
+/// [`Expr::partial_eval`]'s fold for a subtree that isn't fully numeric.
+fn fold_numeric(
+    nodes: &[Arc<Expr>],
+    combine: impl Fn((f64, f64), (f64, f64)) -> (f64, f64),
+    identity: (f64, f64),
+) -> Option<(f64, f64)> {
+    let mut acc = identity;
+    for node in nodes {
+        let value = match node.as_ref() {
+            Expr::Scalar(v) => (*v, 0.0),
+            Expr::Complex(re, im) => (*re, *im),
+            _ => return None,
+        };
+        acc = combine(acc, value);
+    }
+    Some(acc)
+}
+
+/// A real `Scalar` if `im` is exactly `0.0`, otherwise a `Complex` leaf.
+fn complex_or_scalar(re: f64, im: f64) -> Expr {
+    if im == 0.0 {
+        Expr::Scalar(re)
+    } else {
+        Expr::Complex(re, im)
+    }
+}
+
+/// Builds the structural commutator `[a, b] = a*b - b*a` as a `Sum` of
+/// `Product`s, without simplifying. Downstream `simplify`/`to_pauli_sum`
+/// reduce it further.
+pub fn commutator(a: &Arc<Expr>, b: &Arc<Expr>) -> Arc<Expr> {
+    let ab = Arc::new(Expr::Product(vec![a.clone(), b.clone()]));
+    let neg_ba = Arc::new(Expr::Product(vec![
+        Arc::new(Expr::Scalar(-1.0)),
+        Arc::new(Expr::Product(vec![b.clone(), a.clone()])),
+    ]));
+    Arc::new(Expr::Sum(vec![ab, neg_ba]))
+}
+
+/// Builds the structural anticommutator `{a, b} = a*b + b*a` as a `Sum` of
+/// `Product`s, without simplifying.
+pub fn anticommutator(a: &Arc<Expr>, b: &Arc<Expr>) -> Arc<Expr> {
+    let ab = Arc::new(Expr::Product(vec![a.clone(), b.clone()]));
+    let ba = Arc::new(Expr::Product(vec![b.clone(), a.clone()]));
+    Arc::new(Expr::Sum(vec![ab, ba]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_ir::pauli::Pauli;
+
+    #[test]
+    fn qubit_wise_commuting_groups_splits_on_mismatched_operator() {
+        let x0x1 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X), (1, Pauli::X)])));
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let hamiltonian = Expr::Sum(vec![x0x1, x0, z0]);
+
+        let groups = hamiltonian.qubit_wise_commuting_groups().unwrap();
+        assert_eq!(
+            groups,
+            vec![
+                vec![
+                    (1.0, PauliString::new([(0, Pauli::X)])),
+                    (1.0, PauliString::new([(0, Pauli::X), (1, Pauli::X)])),
+                ],
+                vec![(1.0, PauliString::new([(0, Pauli::Z)]))],
+            ]
+        );
+    }
+
+    #[test]
+    fn commuting_groups_merges_strings_that_only_generally_commute() {
+        let x0x1 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X), (1, Pauli::X)])));
+        let z0z1 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z), (1, Pauli::Z)])));
+        let hamiltonian = Expr::Sum(vec![x0x1, z0z1]);
+
+        let groups = hamiltonian.commuting_groups().unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 2);
+    }
+
+    #[test]
+    fn pauli_one_norm_sums_absolute_coefficients() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let two_x0 = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), x0]));
+        let minus_three_z1 = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(-3.0)), z1]));
+        let hamiltonian = Expr::Sum(vec![two_x0, minus_three_z1]);
+        assert_eq!(hamiltonian.pauli_one_norm().unwrap(), 5.0);
+    }
+
+    #[test]
+    fn truncate_drops_terms_below_threshold() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let tiny = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(1e-9)), z1]));
+        let big = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(1.0)), x0.clone()]));
+        let hamiltonian = Expr::Sum(vec![big, tiny]);
+
+        let (truncated, discarded_norm) = hamiltonian.truncate(1e-6).unwrap();
+        assert_eq!(truncated.to_pauli_sum().unwrap(), vec![(1.0, PauliString::new([(0, Pauli::X)]))]);
+        assert!((discarded_norm - 1e-9).abs() < 1e-15);
+    }
+
+    #[test]
+    fn truncate_errors_on_unbound_symbolic_coefficient() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let theta = named("theta");
+        let term = Expr::Product(vec![theta, x0]);
+        assert!(term.truncate(1e-6).is_err());
+    }
+
+    #[test]
+    fn split_by_support_separates_disjoint_qubits() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z2 = Arc::new(Expr::Pauli(PauliString::new([(2, Pauli::Z)])));
+        let sum = Expr::Sum(vec![x0, z2]);
+        let parts = sum.split_by_support().unwrap();
+        assert_eq!(parts.len(), 2);
+    }
+
+    #[test]
+    fn split_by_support_keeps_overlapping_qubits_together() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z0z1 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z), (1, Pauli::Z)])));
+        let sum = Expr::Sum(vec![x0, z0z1]);
+        let parts = sum.split_by_support().unwrap();
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn trace_counts_only_the_identity_term() {
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let identity = Arc::new(Expr::Pauli(PauliString::new([])));
+        let three_identity = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(3.0)), identity]));
+        let hamiltonian = Expr::Sum(vec![z0, three_identity]);
+        assert_eq!(hamiltonian.trace(1).unwrap(), (6.0, 0.0));
+    }
+
+    #[test]
+    fn trace_of_traceless_operator_is_zero() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let hamiltonian = Expr::Sum(vec![x0, z0]);
+        assert_eq!(hamiltonian.trace(1).unwrap(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn identity_coefficient_and_traceless_part_split_constant_offset() {
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let identity = Arc::new(Expr::Pauli(PauliString::new([])));
+        let three_identity = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(3.0)), identity]));
+        let hamiltonian = Expr::Sum(vec![z0.clone(), three_identity]);
+
+        assert_eq!(hamiltonian.identity_coefficient().unwrap(), 3.0);
+        assert_eq!(
+            hamiltonian.traceless_part().unwrap(),
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(1.0)), z0]))
+        );
+    }
+
+    #[test]
+    fn to_dense_matrix_sums_x0_and_z0() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let sum = Expr::Sum(vec![x0, z0]);
+        let matrix = sum.to_dense_matrix(1).unwrap();
+        assert_eq!(
+            matrix,
+            vec![
+                vec![(1.0, 0.0), (1.0, 0.0)],
+                vec![(1.0, 0.0), (-1.0, 0.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn expectation_of_z0_plus_z1_on_two_qubit_state() {
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let hamiltonian = Expr::Sum(vec![z0, z1]);
+
+        // Basis state index 1 (qubit 0 most significant): qubit 0 in |0>,
+        // qubit 1 in |1>, so <Z0> = +1 and <Z1> = -1 and they cancel.
+        let state = [(0.0, 0.0), (1.0, 0.0), (0.0, 0.0), (0.0, 0.0)];
+        let (real, imag) = hamiltonian.expectation(&state, 2).unwrap();
+        assert!((real - 0.0).abs() < 1e-12);
+        assert!(imag.abs() < 1e-12);
+    }
+
+    #[test]
+    fn expectation_errors_on_unbound_symbolic_coefficient() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let theta = named("theta");
+        let term = Expr::Product(vec![theta, x0]);
+        let state = [(1.0, 0.0), (0.0, 0.0)];
+        assert!(term.expectation(&state, 1).is_err());
+    }
+
+    #[test]
+    fn from_openfermion_terms_builds_sum_with_identity() {
+        let terms = vec![
+            (vec![(0, 'X'), (1, 'Z')], 0.5),
+            (vec![], -1.0),
+        ];
+        let expr = Expr::from_openfermion_terms(&terms).unwrap();
+        assert_eq!(
+            expr.as_ref(),
+            &Expr::Sum(vec![
+                Arc::new(Expr::Product(vec![
+                    Arc::new(Expr::Scalar(0.5)),
+                    Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X), (1, Pauli::Z)]))),
+                ])),
+                Arc::new(Expr::Product(vec![
+                    Arc::new(Expr::Scalar(-1.0)),
+                    Arc::new(Expr::Pauli(PauliString::new([]))),
+                ])),
+            ])
+        );
+    }
+
+    #[test]
+    fn from_label_map_builds_sum_with_identity() {
+        let mut map = HashMap::new();
+        map.insert("X0 Z2".to_string(), 0.5);
+        map.insert("I".to_string(), -1.0);
+
+        let expr = Expr::from_label_map(&map).unwrap();
+        let mut terms = expr.to_pauli_sum().unwrap();
+        terms.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let mut expected = vec![
+            (0.5, PauliString::new([(0, Pauli::X), (2, Pauli::Z)])),
+            (-1.0, PauliString::new([])),
+        ];
+        expected.sort_by(|a, b| a.1.cmp(&b.1));
+        assert_eq!(terms, expected);
+    }
+
+    #[test]
+    fn from_label_map_errors_on_unparseable_label() {
+        let mut map = HashMap::new();
+        map.insert("Q0".to_string(), 1.0);
+        assert!(Expr::from_label_map(&map).is_err());
+    }
+
+    #[test]
+    fn label_map_round_trips_through_from_label_map() {
+        let mut map = HashMap::new();
+        map.insert("X0 Z2".to_string(), 0.5);
+        map.insert("I".to_string(), -1.0);
+
+        let expr = Expr::from_label_map(&map).unwrap();
+        let exported = expr.to_label_map().unwrap();
+        assert_eq!(exported, map);
+    }
+
+    #[test]
+    fn to_label_map_errors_on_unbound_symbol() {
+        let expr = Expr::Symbol(Symbol::new("theta"));
+        assert!(expr.to_label_map().is_err());
+    }
+
+    #[test]
+    fn openfermion_round_trip_preserves_terms_up_to_ordering() {
+        let terms = vec![
+            (vec![(0, 'X'), (1, 'Z')], 0.5),
+            (vec![], -1.0),
+        ];
+        let imported = Expr::from_openfermion_terms(&terms).unwrap();
+        let mut exported = imported.to_openfermion_terms().unwrap();
+        exported.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut expected = terms;
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(exported, expected);
+    }
+
+    #[test]
+    fn to_qiskit_labels_places_qubit_zero_rightmost() {
+        let x0 = Expr::Pauli(PauliString::new([(0, Pauli::X)]));
+        let labels = x0.to_qiskit_labels(3).unwrap();
+        assert_eq!(labels, vec![("IIX".to_string(), (1.0, 0.0))]);
+    }
+
+    #[test]
+    fn to_qiskit_labels_errors_when_support_too_wide() {
+        let z2 = Expr::Pauli(PauliString::new([(2, Pauli::Z)]));
+        assert!(z2.to_qiskit_labels(2).is_err());
+    }
+
+    #[test]
+    fn bind_touches_only_matching_leaves() {
+        let theta = Arc::new(Expr::Symbol(Symbol::new("theta")));
+        let phi = Arc::new(Expr::Symbol(Symbol::new("phi")));
+        let pauli = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let sum = Expr::Sum(vec![theta, phi.clone(), pauli.clone()]);
+
+        let bound = sum.bind("theta", 1.5);
+        match bound.as_ref() {
+            Expr::Sum(terms) => {
+                assert_eq!(
+                    terms[0].as_ref(),
+                    &Expr::Symbol(Symbol::new("theta").bind(1.5))
+                );
+                assert_eq!(terms[1], phi);
+                assert_eq!(terms[2], pauli);
+            }
+            _ => panic!("expected Sum"),
+        }
+    }
+
+    #[test]
+    fn bind_all_binds_multiple_symbols() {
+        let expr = Expr::Sum(vec![
+            Arc::new(Expr::Symbol(Symbol::new("theta"))),
+            Arc::new(Expr::Symbol(Symbol::new("phi"))),
+        ]);
+        let mut map = HashMap::new();
+        map.insert("theta".to_string(), 1.0);
+        map.insert("phi".to_string(), 2.0);
+
+        let bound = expr.bind_all(&map);
+        match bound.as_ref() {
+            Expr::Sum(terms) => {
+                assert_eq!(terms[0].as_ref(), &Expr::Symbol(Symbol::new("theta").bind(1.0)));
+                assert_eq!(terms[1].as_ref(), &Expr::Symbol(Symbol::new("phi").bind(2.0)));
+            }
+            _ => panic!("expected Sum"),
+        }
+    }
+
+    #[test]
+    fn bind_vector_binds_each_name_to_its_value() {
+        let expr = Expr::Sum(vec![
+            Arc::new(Expr::Symbol(Symbol::new("theta"))),
+            Arc::new(Expr::Symbol(Symbol::new("phi"))),
+        ]);
+
+        let bound = expr.bind_vector(&["theta", "phi"], &[1.0, 2.0]).unwrap();
+        match bound.as_ref() {
+            Expr::Sum(terms) => {
+                assert_eq!(terms[0].as_ref(), &Expr::Symbol(Symbol::new("theta").bind(1.0)));
+                assert_eq!(terms[1].as_ref(), &Expr::Symbol(Symbol::new("phi").bind(2.0)));
+            }
+            _ => panic!("expected Sum"),
+        }
+    }
+
+    #[test]
+    fn bind_vector_errors_on_length_mismatch() {
+        let expr = Expr::Symbol(Symbol::new("theta"));
+        assert!(expr.bind_vector(&["theta", "phi"], &[1.0]).is_err());
+    }
+
+    #[test]
+    fn substitute_into_nested_product() {
+        let two_theta = Arc::new(Expr::Product(vec![
+            Arc::new(Expr::Scalar(2.0)),
+            Arc::new(Expr::Symbol(Symbol::new("theta"))),
+        ]));
+        let phi = Arc::new(Expr::Symbol(Symbol::new("phi")));
+        let psi = Arc::new(Expr::Symbol(Symbol::new("psi")));
+        let product = Expr::Product(vec![phi, psi.clone()]);
+
+        let substituted = product.substitute("phi", &two_theta);
+        match substituted.as_ref() {
+            Expr::Product(factors) => {
+                assert_eq!(factors[0], two_theta);
+                assert_eq!(factors[1], psi);
+            }
+            _ => panic!("expected Product"),
+        }
+    }
+
+    #[test]
+    fn diff_applies_sum_and_product_rules() {
+        use crate::core_ir::Canonical;
+
+        // d/dtheta (theta*X0 + theta^2) = X0 + theta + theta, i.e. `X0 +
+        // 2*theta` up to combining the two identical `theta` terms, which
+        // `simplify` doesn't do (it only folds scalar terms).
+        let theta = Arc::new(Expr::Symbol(Symbol::new("theta")));
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let theta_x0 = Arc::new(Expr::Product(vec![theta.clone(), x0.clone()]));
+        let theta_squared = theta.pow(2);
+        let expr = Expr::Sum(vec![theta_x0, theta_squared]);
+
+        let derivative = expr.diff("theta").simplify().canonical();
+        let expected = Expr::Sum(vec![theta.clone(), theta, x0]).simplify().canonical();
+        assert_eq!(derivative, expected);
+    }
+
+    #[test]
+    fn diff_of_constants_is_zero() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        assert_eq!(x0.diff("theta").as_ref(), &Expr::Scalar(0.0));
+        assert_eq!(Expr::Scalar(3.0).diff("theta").as_ref(), &Expr::Scalar(0.0));
+        assert_eq!(
+            Expr::Symbol(Symbol::new("phi")).diff("theta").as_ref(),
+            &Expr::Scalar(0.0)
+        );
+    }
+
+    #[test]
+    fn try_eval_bound_expression() {
+        let theta = Arc::new(Expr::Symbol(Symbol::new("theta").bind(4.0)));
+        let three = Arc::new(Expr::Scalar(3.0));
+        let sum = Arc::new(Expr::Sum(vec![theta, three]));
+        let two = Arc::new(Expr::Scalar(2.0));
+        let product = Expr::Product(vec![two, sum]);
+        assert_eq!(product.try_eval(), Ok(14.0));
+    }
+
+    #[test]
+    fn partial_eval_folds_bound_sum_but_leaves_pauli_symbolic() {
+        let bound_theta = Arc::new(Expr::Symbol(Symbol::new("theta").bind(2.0)));
+        let one = Arc::new(Expr::Scalar(1.0));
+        let sum = Arc::new(Expr::Sum(vec![bound_theta, one]));
+        let product = Expr::Product(vec![sum, pauli_x0()]);
+
+        let reduced = product.partial_eval();
+        assert_eq!(
+            reduced.as_ref(),
+            &Expr::Product(vec![Arc::new(Expr::Scalar(3.0)), pauli_x0()])
+        );
+    }
+
+    #[test]
+    fn partial_eval_leaves_free_symbol_untouched() {
+        let expr = Expr::Sum(vec![named("theta"), Arc::new(Expr::Scalar(1.0))]);
+        assert_eq!(expr.partial_eval().as_ref(), &expr);
+    }
+
+    #[test]
+    fn try_eval_of_real_complex_bound_symbol_succeeds() {
+        let phi = Expr::Symbol(Symbol::new("phi").bind_complex(5.0, 0.0));
+        assert_eq!(phi.try_eval(), Ok(5.0));
+    }
+
+    #[test]
+    fn try_eval_of_non_real_complex_bound_symbol_errors() {
+        let phi = Expr::Symbol(Symbol::new("phi").bind_complex(1.0, 2.0));
+        assert!(phi.try_eval().is_err());
+    }
+
+    #[test]
+    fn partial_eval_folds_complex_bound_symbol_to_complex_leaf() {
+        let phi = Arc::new(Expr::Symbol(Symbol::new("phi").bind_complex(1.0, 2.0)));
+        assert_eq!(phi.partial_eval(), Arc::new(Expr::Complex(1.0, 2.0)));
+    }
+
+    #[test]
+    fn try_eval_unbound_symbol_errors() {
+        let theta = Expr::Symbol(Symbol::new("theta"));
+        assert!(theta.try_eval().is_err());
+    }
+
+    fn pauli_x0() -> Arc<Expr> {
+        Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])))
+    }
+
+    #[test]
+    fn simplify_folds_sum_scalars() {
+        let sum = Expr::Sum(vec![
+            Arc::new(Expr::Scalar(2.0)),
+            Arc::new(Expr::Scalar(3.0)),
+            pauli_x0(),
+        ]);
+        assert_eq!(
+            sum.simplify().as_ref(),
+            &Expr::Sum(vec![Arc::new(Expr::Scalar(5.0)), pauli_x0()])
+        );
+    }
+
+    #[test]
+    fn simplify_folds_product_scalars() {
+        let product = Expr::Product(vec![
+            Arc::new(Expr::Scalar(2.0)),
+            Arc::new(Expr::Scalar(3.0)),
+            pauli_x0(),
+        ]);
+        assert_eq!(
+            product.simplify().as_ref(),
+            &Expr::Product(vec![Arc::new(Expr::Scalar(6.0)), pauli_x0()])
+        );
+    }
+
+    #[test]
+    fn simplify_drops_additive_zero() {
+        let sum = Expr::Sum(vec![Arc::new(Expr::Scalar(0.0)), pauli_x0()]);
+        assert_eq!(sum.simplify().as_ref(), pauli_x0().as_ref());
+    }
+
+    #[test]
+    fn simplify_drops_multiplicative_one() {
+        let product = Expr::Product(vec![Arc::new(Expr::Scalar(1.0)), pauli_x0()]);
+        assert_eq!(product.simplify().as_ref(), pauli_x0().as_ref());
+    }
+
+    #[test]
+    fn simplify_collapses_product_with_zero() {
+        let product = Expr::Product(vec![Arc::new(Expr::Scalar(0.0)), pauli_x0()]);
+        assert_eq!(product.simplify(), Arc::new(Expr::Scalar(0.0)));
+    }
+
+    #[test]
+    fn simplify_cancels_opposite_terms_to_zero() {
+        let x0 = pauli_x0();
+        let sum = Expr::Sum(vec![
+            x0.clone(),
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(-1.0)), x0])),
+        ]);
+        assert_eq!(sum.simplify(), Arc::new(Expr::Scalar(0.0)));
+    }
+
+    #[test]
+    fn simplify_drops_a_term_whose_coefficient_is_zero() {
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let sum = Expr::Sum(vec![
+            pauli_x0(),
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(0.0)), z1])),
+        ]);
+        assert_eq!(sum.simplify().as_ref(), pauli_x0().as_ref());
+    }
+
+    #[test]
+    fn split_coefficient_folds_embedded_scalar_factors() {
+        let x0 = pauli_x0();
+        let product = Expr::Product(vec![
+            Arc::new(Expr::Scalar(2.0)),
+            x0.clone(),
+            Arc::new(Expr::Scalar(3.0)),
+        ]);
+        let (coeff, remainder) = product.split_coefficient();
+        assert_eq!(coeff, (6.0, 0.0));
+        assert_eq!(remainder, x0);
+    }
+
+    #[test]
+    fn split_coefficient_of_bare_scalar_and_non_product() {
+        assert_eq!(Expr::Scalar(5.0).split_coefficient(), ((5.0, 0.0), Expr::one()));
+        let x0 = pauli_x0();
+        assert_eq!(x0.split_coefficient(), ((1.0, 0.0), x0));
+    }
+
+    #[test]
+    fn pow_zero_is_one() {
+        assert_eq!(pauli_x0().pow(0), Expr::one());
+    }
+
+    #[test]
+    fn pow_two_of_pauli_canonicalizes_to_identity() {
+        let x0 = pauli_x0();
+        let squared = x0.pow(2);
+        let identity = Arc::new(Expr::Pauli(PauliString::new([])));
+        assert_eq!(squared.canonical_algebraic(), identity.canonical_algebraic());
+    }
+
+    #[test]
+    fn tensor_shifts_the_other_operands_qubit_indices() {
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let x1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::X)])));
+
+        let product = z0.tensor(&x0, 1);
+        assert_eq!(product, Arc::new(Expr::Product(vec![z0, x1])));
+    }
+
+    #[test]
+    fn simplify_folds_i_times_i_to_minus_one() {
+        let product = Expr::Product(vec![Expr::i(), Expr::i()]);
+        assert_eq!(product.simplify(), Arc::new(Expr::Scalar(-1.0)));
+    }
+
+    fn named(n: &str) -> Arc<Expr> {
+        Arc::new(Expr::Symbol(Symbol::new(n)))
+    }
+
+    #[test]
+    fn expand_distributes_two_sums() {
+        let a = named("a");
+        let b = named("b");
+        let c = named("c");
+        let d = named("d");
+        let lhs = Arc::new(Expr::Sum(vec![a.clone(), b.clone()]));
+        let rhs = Arc::new(Expr::Sum(vec![c.clone(), d.clone()]));
+        let product = Expr::Product(vec![lhs, rhs]);
+
+        let expanded = product.expand();
+        assert_eq!(
+            expanded.as_ref(),
+            &Expr::Sum(vec![
+                Arc::new(Expr::Product(vec![a.clone(), c.clone()])),
+                Arc::new(Expr::Product(vec![a, d.clone()])),
+                Arc::new(Expr::Product(vec![b.clone(), c])),
+                Arc::new(Expr::Product(vec![b, d])),
+            ])
+        );
+    }
+
+    #[test]
+    fn to_pauli_sum_x0_squared_is_identity() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let product = Expr::Product(vec![x0.clone(), x0]);
+        let sum = product.to_pauli_sum().unwrap();
+        assert_eq!(sum, vec![(1.0, PauliString::new([]))]);
+    }
+
+    #[test]
+    fn to_pauli_sum_errors_on_non_real_phase_from_overlapping_paulis() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let y0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Y)])));
+        // [X0, Y0] = 2i*Z0: the accumulated phase from X0*Y0 is genuinely
+        // imaginary, so this must error rather than silently drop to 0.
+        assert!(commutator(&x0, &y0).to_pauli_sum().is_err());
+    }
+
+    #[test]
+    fn to_pauli_sum_merges_like_terms() {
+        let z0 = || Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let two_z0 = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), z0()]));
+        let three_z0 = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(3.0)), z0()]));
+        let sum = Expr::Sum(vec![two_z0, three_z0]);
+        let result = sum.to_pauli_sum().unwrap();
+        assert_eq!(result, vec![(5.0, PauliString::new([(0, Pauli::Z)]))]);
+    }
+
+    #[test]
+    fn to_pauli_sum_ordering_is_deterministic_across_builds() {
+        let z0 = || Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let x1 = || Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::X)])));
+        let identity = || Arc::new(Expr::Pauli(PauliString::new([])));
+
+        let first = Expr::Sum(vec![z0(), x1(), identity()]).to_pauli_sum().unwrap();
+        let second = Expr::Sum(vec![identity(), x1(), z0()]).to_pauli_sum().unwrap();
+        assert_eq!(first, second);
+
+        let mut sorted = first.clone();
+        sorted.sort_by(|a, b| a.1.cmp(&b.1));
+        assert_eq!(first, sorted, "to_pauli_sum should already be sorted by PauliString");
+    }
+
+    #[test]
+    fn num_pauli_terms_counts_distinct_nonzero_strings() {
+        let x0 = || Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = || Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let sum = Expr::Sum(vec![x0(), z1()]);
+        assert_eq!(sum.num_pauli_terms().unwrap(), 2);
+    }
+
+    #[test]
+    fn num_pauli_terms_drops_terms_that_cancel_to_zero() {
+        let x0 = || Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let minus_two_x0 = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(-2.0)), x0()]));
+        let sum = Expr::Sum(vec![x0(), x0(), minus_two_x0]);
+        assert_eq!(sum.num_pauli_terms().unwrap(), 0);
+    }
+
+    #[test]
+    fn diff_terms_reports_the_single_differing_coefficient() {
+        let x0 = || Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = || Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+
+        let a = Expr::Sum(vec![x0(), z1()]);
+        let b = Expr::Sum(vec![x0(), Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), z1()]))]);
+
+        assert_eq!(
+            a.diff_terms(&b).unwrap(),
+            vec![(PauliString::new([(1, Pauli::Z)]), 1.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn diff_terms_reports_a_term_present_on_only_one_side() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+
+        let a = Expr::Sum(vec![x0.clone(), z1]);
+        let b = Expr::Sum(vec![x0]);
+
+        assert_eq!(
+            a.diff_terms(&b).unwrap(),
+            vec![(PauliString::new([(1, Pauli::Z)]), 1.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn diff_terms_is_empty_for_equal_operators() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let a = Expr::Sum(vec![x0.clone(), x0.clone()]);
+        let b = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), x0]));
+        assert_eq!(a.diff_terms(&b).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn equal_up_to_scalar_finds_the_common_ratio() {
+        let x0 = || Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = || Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let a = Expr::Sum(vec![x0(), z1()]);
+        let two = Arc::new(Expr::Scalar(2.0));
+        let b = Expr::Product(vec![two, Arc::new(Expr::Sum(vec![x0(), z1()]))]);
+
+        assert_eq!(b.equal_up_to_scalar(&a), Some(2.0));
+        assert_eq!(a.equal_up_to_scalar(&b), Some(0.5));
+    }
+
+    #[test]
+    fn equal_up_to_scalar_rejects_unrelated_operators() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        assert_eq!(x0.equal_up_to_scalar(&z1), None);
+    }
+
+    #[test]
+    fn to_symbolic_pauli_sum_merges_symbolic_coefficients() {
+        let x0 = || Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let theta_x0 = Arc::new(Expr::Product(vec![named("theta"), x0()]));
+        let phi_x0 = Arc::new(Expr::Product(vec![named("phi"), x0()]));
+        let sum = Expr::Sum(vec![theta_x0, phi_x0]);
+
+        let result = sum.to_symbolic_pauli_sum().unwrap();
+        assert_eq!(result.len(), 1);
+        let (coeff, pauli) = &result[0];
+        assert_eq!(pauli, &PauliString::new([(0, Pauli::X)]));
+        assert_eq!(coeff.as_ref(), &Expr::Sum(vec![named("theta"), named("phi")]));
+    }
+
+    #[test]
+    fn to_symbolic_pauli_sum_folds_non_real_phase_into_complex_coefficient() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let y0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Y)])));
+        // X0 * Y0 = i*Z0: the accumulated phase is genuinely imaginary, so
+        // it must survive as an Expr::Complex coefficient, not get dropped.
+        let product = Expr::Product(vec![x0, y0]);
+
+        let result = product.to_symbolic_pauli_sum().unwrap();
+        assert_eq!(result.len(), 1);
+        let (coeff, pauli) = &result[0];
+        assert_eq!(pauli, &PauliString::new([(0, Pauli::Z)]));
+        assert_eq!(coeff.as_ref(), &Expr::Complex(0.0, 1.0));
+    }
+
+    #[test]
+    fn trotter_step_repeats_the_coefficient_divided_sequence() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let two_x0 = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), x0]));
+        let three_z1 = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(3.0)), z1]));
+        let hamiltonian = Expr::Sum(vec![two_x0, three_z1]);
+
+        let steps = hamiltonian.trotter_step(2).unwrap();
+        let expected_per_step = vec![
+            (1.0, PauliString::new([(0, Pauli::X)])),
+            (1.5, PauliString::new([(1, Pauli::Z)])),
+        ];
+        let mut expected = expected_per_step.clone();
+        expected.extend(expected_per_step);
+        assert_eq!(steps, expected);
+    }
+
+    #[test]
+    fn trotter_step_rejects_zero_steps() {
+        let x0 = Expr::Pauli(PauliString::new([(0, Pauli::X)]));
+        assert!(x0.trotter_step(0).is_err());
+    }
+
+    #[test]
+    fn free_symbols_deduplicates_repeated_names() {
+        let theta = named("theta");
+        let product = Arc::new(Expr::Product(vec![theta.clone(), theta.clone()]));
+        let phi = named("phi");
+        let sum = Expr::Sum(vec![product, phi]);
+
+        let symbols = sum.free_symbols();
+        assert_eq!(
+            symbols,
+            vec![
+                Symbol::new("theta"),
+                Symbol::new("phi"),
+            ]
+        );
+    }
+
+    #[test]
+    fn walk_visits_every_leaf_exactly_once() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let theta = named("theta");
+        let product = Arc::new(Expr::Product(vec![theta.clone(), x0.clone()]));
+        let scalar = Arc::new(Expr::scalar(2.0).unwrap().as_ref().clone());
+        let sum = Expr::Sum(vec![product, scalar]);
+
+        let mut leaves = Vec::new();
+        sum.walk(&mut |node| {
+            if matches!(node, Expr::Scalar(_) | Expr::Symbol(_) | Expr::Pauli(_)) {
+                leaves.push(node.clone());
+            }
+        });
+
+        assert_eq!(leaves, vec![theta.as_ref().clone(), x0.as_ref().clone(), Expr::Scalar(2.0)]);
+    }
+
+    #[test]
+    fn map_bottom_up_can_double_every_scalar() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let two = Arc::new(Expr::scalar(2.0).unwrap().as_ref().clone());
+        let product = Arc::new(Expr::Product(vec![two, x0.clone()]));
+        let three = Arc::new(Expr::scalar(3.0).unwrap().as_ref().clone());
+        let sum = Expr::Sum(vec![product, three]);
+
+        let doubled = sum.map_bottom_up(|node| match node {
+            Expr::Scalar(v) => Some(Arc::new(Expr::Scalar(v * 2.0))),
+            _ => None,
+        });
+
+        let expected = Expr::Sum(vec![
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(4.0)), x0])),
+            Arc::new(Expr::Scalar(6.0)),
+        ]);
+        assert_eq!(doubled.as_ref(), &expected);
+    }
+
+    #[test]
+    fn dagger_reverses_product_order() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let y1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Y)])));
+        let product = Expr::Product(vec![x0.clone(), y1.clone()]);
+        assert_eq!(product.dagger().as_ref(), &Expr::Product(vec![y1, x0]));
+    }
+
+    #[test]
+    fn dagger_of_i_times_x0_is_minus_i_times_x0() {
+        // `dagger` reverses factor order (operators don't commute), so
+        // `dagger(i*X0)` is `X0 * (-i)`, not `(-i) * X0`.
+        let x0 = pauli_x0();
+        let product = Expr::Product(vec![Expr::i(), x0.clone()]);
+        let expected = Arc::new(Expr::Product(vec![x0, Arc::new(Expr::Complex(0.0, -1.0))]));
+        assert_eq!(product.dagger(), expected);
+    }
+
+    #[test]
+    fn dagger_negates_imaginary_part_of_bound_complex_symbol() {
+        let phi = Symbol::new("phi").bind_complex(1.0, -2.0);
+        let expr = Arc::new(Expr::Symbol(phi));
+        let expected = Arc::new(Expr::Symbol(Symbol::new("phi").bind_complex(1.0, 2.0)));
+        assert_eq!(expr.dagger(), expected);
+    }
+
+    #[test]
+    fn dagger_distributes_over_sum() {
+        let a = named("a");
+        let b = named("b");
+        let sum = Expr::Sum(vec![a.clone(), b.clone()]);
+        assert_eq!(sum.dagger().as_ref(), &Expr::Sum(vec![a, b]));
+    }
+
+    #[test]
+    fn is_hermitian_for_sum_of_paulis() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let sum = Expr::Sum(vec![x0, z1]);
+        assert!(sum.is_hermitian());
+    }
+
+    #[test]
+    fn is_not_hermitian_for_noncommuting_product() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let product = Expr::Product(vec![x0, z0]);
+        assert!(!product.is_hermitian());
+    }
+
+    #[test]
+    fn commutator_builds_ab_minus_ba() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let comm = commutator(&x0, &z0);
+        match comm.as_ref() {
+            Expr::Sum(terms) => {
+                assert_eq!(terms.len(), 2);
+                assert_eq!(
+                    terms[0].as_ref(),
+                    &Expr::Product(vec![x0.clone(), z0.clone()])
+                );
+                assert_eq!(
+                    terms[1].as_ref(),
+                    &Expr::Product(vec![
+                        Arc::new(Expr::Scalar(-1.0)),
+                        Arc::new(Expr::Product(vec![z0, x0])),
+                    ])
+                );
+            }
+            _ => panic!("expected Sum"),
+        }
+    }
+
+    #[test]
+    fn anticommutator_builds_ab_plus_ba() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let anti = anticommutator(&x0, &z0);
+        assert_eq!(
+            anti.as_ref(),
+            &Expr::Sum(vec![
+                Arc::new(Expr::Product(vec![x0.clone(), z0.clone()])),
+                Arc::new(Expr::Product(vec![z0, x0])),
+            ])
+        );
+    }
+
+    #[test]
+    fn operator_overloads_match_explicit_constructors() {
+        let a = named("a");
+        let b = named("b");
+
+        assert_eq!(
+            a.as_ref() + b.as_ref(),
+            Arc::new(Expr::Sum(vec![a.clone(), b.clone()]))
+        );
+        assert_eq!(
+            a.as_ref() * b.as_ref(),
+            Arc::new(Expr::Product(vec![a.clone(), b.clone()]))
+        );
+        assert_eq!(
+            a.as_ref() - b.as_ref(),
+            Arc::new(Expr::Sum(vec![
+                a,
+                Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(-1.0)), b])),
+            ]))
+        );
+    }
+
+    #[test]
+    fn neg_wraps_in_minus_one_product() {
+        let a = named("a");
+        assert_eq!(
+            -a.as_ref(),
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(-1.0)), a]))
+        );
+    }
+
+    #[test]
+    fn sub_helper_matches_sub_operator() {
+        let a = named("a");
+        let b = named("b");
+        assert_eq!(sub(&a, &b), a.as_ref() - b.as_ref());
+    }
+
+    #[test]
+    fn from_conversions_build_expected_leaves() {
+        let scalar: Expr = 2.0.into();
+        assert_eq!(scalar, Expr::Scalar(2.0));
+
+        let symbol: Expr = Symbol::new("theta").into();
+        assert_eq!(symbol, Expr::Symbol(Symbol::new("theta")));
+
+        let pauli: Expr = PauliString::new([(0, Pauli::X)]).into();
+        assert_eq!(pauli, Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+
+        // The conversions above target `Expr`; `Arc<Expr>` comes for free
+        // through std's blanket `impl<T> From<T> for Arc<T>`.
+        let boxed: Arc<Expr> = Expr::from(7.0).into();
+        assert_eq!(boxed, Arc::new(Expr::Scalar(7.0)));
+    }
+
+    #[test]
+    fn num_qubits_is_max_index_plus_one() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z3 = Arc::new(Expr::Pauli(PauliString::new([(3, Pauli::Z)])));
+        let sum = Expr::Sum(vec![x0, z3]);
+        assert_eq!(sum.num_qubits(), 4);
+    }
+
+    #[test]
+    fn num_qubits_is_zero_without_pauli_leaves() {
+        let expr = Expr::Sum(vec![Arc::new(Expr::Scalar(1.0)), named("theta")]);
+        assert_eq!(expr.num_qubits(), 0);
+    }
+
+    #[test]
+    fn depth_and_size_of_nested_expression() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let theta = named("theta");
+        let product = Arc::new(Expr::Product(vec![theta, x0]));
+        let scalar = Arc::new(Expr::Scalar(1.0));
+        let expr = Expr::Sum(vec![product, scalar]);
+
+        // Sum -> Product -> {Symbol, Pauli}, and Sum -> Scalar.
+        assert_eq!(expr.depth(), 3);
+        // Sum, Product, Symbol, Pauli, Scalar.
+        assert_eq!(expr.size(), 5);
+    }
+
+    #[test]
+    fn ordering_sorts_scalar_symbol_pauli_deterministically() {
+        let pauli = Expr::Pauli(PauliString::new([(0, Pauli::X)]));
+        let symbol = Expr::Symbol(Symbol::new("theta"));
+        let scalar = Expr::Scalar(1.0);
+
+        let mut terms = vec![pauli.clone(), symbol.clone(), scalar.clone()];
+        terms.sort();
+        assert_eq!(terms, vec![scalar, symbol, pauli]);
+    }
+
+    #[test]
+    fn sum_ord_is_structural_and_order_sensitive_unlike_canonical() {
+        use crate::core_ir::Canonical;
+
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let forward = Expr::Sum(vec![x0.clone(), z1.clone()]);
+        let backward = Expr::Sum(vec![z1, x0]);
+
+        // The derived-style `Ord`/`PartialEq` sees these as different
+        // expressions, since it compares `Sum`'s `Vec<Arc<Expr>>` content
+        // in order rather than as an unordered multiset.
+        assert_ne!(forward, backward);
+        assert_ne!(forward.cmp(&backward), std::cmp::Ordering::Equal);
+
+        // `canonical()` is the semantic order: it sorts `Sum`/`Product`
+        // children first, so reordered-but-equivalent sums compare equal.
+        assert_eq!(forward.canonical(), backward.canonical());
+    }
+
+    #[test]
+    fn try_sum_and_try_product_reject_empty_input() {
+        assert!(Expr::try_sum(vec![]).is_err());
+        assert!(Expr::try_product(vec![]).is_err());
+    }
+
+    #[test]
+    fn try_sum_and_try_product_accept_a_single_term() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+
+        let sum = Expr::try_sum(vec![x0.clone()]).unwrap();
+        assert_eq!(sum, Arc::new(Expr::Sum(vec![x0.clone()])));
+
+        let product = Expr::try_product(vec![x0.clone()]).unwrap();
+        assert_eq!(product, Arc::new(Expr::Product(vec![x0])));
+    }
+
+    #[test]
+    fn scalar_constructor_rejects_non_finite_values() {
+        assert!(Expr::scalar(1.5).is_ok());
+        assert!(Expr::scalar(f64::NAN).is_err());
+        assert!(Expr::scalar(f64::INFINITY).is_err());
+        assert!(Expr::scalar(f64::NEG_INFINITY).is_err());
+    }
+
+    #[test]
+    fn complex_constructor_collapses_real_im_to_scalar() {
+        assert_eq!(Expr::complex(2.0, 0.0).unwrap(), Arc::new(Expr::Scalar(2.0)));
+        assert_eq!(Expr::complex(0.0, 1.0).unwrap(), Expr::i());
+        assert!(Expr::complex(f64::NAN, 1.0).is_err());
+    }
+
+    #[test]
+    fn display_renders_complex_with_sign() {
+        assert_eq!(Expr::Complex(1.0, 2.0).to_string(), "(1+2i)");
+        assert_eq!(Expr::Complex(1.0, -2.0).to_string(), "(1-2i)");
+    }
+
+    #[test]
+    fn canonical_algebraic_merges_repeated_pauli_term() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let repeated = Expr::Sum(vec![x0.clone(), x0.clone()]);
+        let doubled = Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), x0]);
+        assert_eq!(repeated.canonical_algebraic(), doubled.canonical_algebraic());
+    }
+
+    #[test]
+    fn canonical_algebraic_folds_non_real_phase_into_complex_coefficient() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let y0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Y)])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let product = Expr::Product(vec![x0, y0]);
+        let expected = Expr::Product(vec![Arc::new(Expr::Complex(0.0, 1.0)), z0]);
+        assert_eq!(product.canonical_algebraic(), expected.canonical_algebraic());
+    }
+
+    #[test]
+    fn canonical_commuting_sorted_reorders_disjoint_factors() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let x1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::X)])));
+        let a = Expr::Product(vec![x1.clone(), x0.clone()]);
+        let b = Expr::Product(vec![x0, x1]);
+        assert_eq!(
+            a.canonical_commuting_sorted(),
+            b.canonical_commuting_sorted()
+        );
+    }
+
+    #[test]
+    fn canonical_commuting_sorted_leaves_noncommuting_neighbors_fixed() {
+        // `X0` sorts before `Z0` by `Ord`, so placing `Z0` first is "out of
+        // order"; a commuting-only sort would swap them back, but since they
+        // don't commute on the same qubit the order must be preserved.
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let product = Expr::Product(vec![z0.clone(), x0.clone()]);
+        assert_eq!(
+            product.canonical_commuting_sorted().as_ref(),
+            &Expr::Product(vec![z0, x0])
+        );
+    }
+
+    #[test]
+    fn approx_eq_tolerates_tiny_scalar_drift_but_not_at_zero_tolerance() {
+        let a = Expr::Scalar(1.0);
+        let b = Expr::Scalar(1.0 + 1e-12);
+        assert!(a.approx_eq(&b, 1e-9));
+        assert!(!a.approx_eq(&b, 0.0));
+    }
+
+    #[test]
+    fn approx_eq_compares_sums_regardless_of_term_order() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let a = Expr::Sum(vec![x0.clone(), z1.clone()]);
+        let b = Expr::Sum(vec![z1, x0]);
+        assert!(a.approx_eq(&b, 0.0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips_a_nested_expression() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let theta = Arc::new(Expr::Symbol(Symbol::new("theta").bind(1.5)));
+        let expr = Expr::Sum(vec![
+            Arc::new(Expr::Product(vec![theta, x0])),
+            Arc::new(Expr::Scalar(2.0)),
+        ]);
+
+        let json = serde_json::to_string(&expr).unwrap();
+        let round_tripped: Expr = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, expr);
+    }
+
+    #[test]
+    fn to_latex_renders_nested_sum_and_product_with_subscripts() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let sum = Arc::new(Expr::Sum(vec![x0, z1]));
+        let product = Expr::Product(vec![named("theta"), sum]);
+        assert_eq!(product.to_latex(), r"theta \cdot (X_{0} + Z_{1})");
+    }
+
+    #[test]
+    fn to_latex_renders_identity_pauli() {
+        let identity = Expr::Pauli(PauliString::new([]));
+        assert_eq!(identity.to_latex(), "I");
+    }
+
+    #[test]
+    fn display_parenthesizes_sum_nested_in_product() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let sum = Arc::new(Expr::Sum(vec![x0, z1]));
+        let product = Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), sum]);
+        assert_eq!(product.to_string(), "2 * (X0 + Z1)");
+    }
+
+    #[test]
+    fn display_with_rounds_coefficients_and_drops_unit_factors() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let expr = Expr::Sum(vec![
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(1.0)), x0])),
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(0.33333)), z1])),
+        ]);
+        assert_eq!(expr.display_with(2), "X0 + 0.33 * Z1");
+    }
+
+    #[test]
+    fn display_with_still_parenthesizes_sum_nested_in_product() {
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let z1 = Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)])));
+        let sum = Arc::new(Expr::Sum(vec![x0, z1]));
+        let product = Expr::Product(vec![Arc::new(Expr::Scalar(2.0)), sum]);
+        assert_eq!(product.display_with(2), "2.00 * (X0 + Z1)");
+    }
+
+    #[test]
+    fn parse_round_trips_through_display() {
+        let expr = Expr::parse("2 * X0 + theta * (Z1 + Z2)").unwrap();
+        let rendered = expr.to_string();
+        let reparsed = Expr::parse(&rendered).unwrap();
+        assert_eq!(reparsed, expr);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_input() {
+        assert!(Expr::parse("1 +").is_err());
+        assert!(Expr::parse("(a + b").is_err());
+    }
+
+    #[test]
+    fn zero_and_one_constants() {
+        assert_eq!(Expr::zero(), Arc::new(Expr::Scalar(0.0)));
+        assert_eq!(Expr::one(), Arc::new(Expr::Scalar(1.0)));
+    }
+}
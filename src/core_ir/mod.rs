@@ -0,0 +1,21 @@
+//! Core intermediate representation: Pauli operators and the types built on them.
+
+mod builder;
+mod canonical;
+mod expr;
+mod parser;
+mod pauli;
+mod pauli_string;
+mod phase;
+mod sparse;
+mod symbol;
+
+pub use bitvec::vec::BitVec;
+pub use builder::ExprBuilder;
+pub use canonical::{Canonical, Canonicalized, Flatten};
+pub use expr::{anticommutator, commutator, sub, Expr, OpenFermionTerm, QiskitTerm};
+pub use pauli::Pauli;
+pub use pauli_string::{DenseMatrix, PauliString};
+pub use phase::Phase;
+pub use sparse::SparseMatrix;
+pub use symbol::Symbol;
@@ -0,0 +1,197 @@
+//! A small recursive-descent parser for infix `Expr` syntax, e.g.
+//! `2.0 * X0 + theta * (Z1 + Z2)`.
+//!
+//! Supports `+` and `*` with the usual precedence (`*` binds tighter than
+//! `+`) and left-associativity, parentheses, numeric literals, sparse
+//! Pauli tokens (`X0`, `Z12`, ...), and bare identifiers as named symbols.
+
+use std::sync::Arc;
+
+use crate::error::CationError;
+
+use super::expr::Expr;
+use super::pauli_string::PauliString;
+use super::symbol::Symbol;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Plus,
+    Star,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, CationError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '+' {
+            tokens.push(Token::Plus);
+            i += 1;
+        } else if c == '*' {
+            tokens.push(Token::Star);
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text
+                .parse()
+                .map_err(|_| CationError::Other(format!("invalid number literal {text:?}")))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            return Err(CationError::Other(format!("unexpected character {c:?} in expression")));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Whether `ident` is a sparse Pauli token like `X0` or `I12`: a single
+/// `I`/`X`/`Y`/`Z` letter followed by one or more digits.
+fn is_pauli_token(ident: &str) -> bool {
+    let mut chars = ident.chars();
+    matches!(chars.next(), Some('I' | 'X' | 'Y' | 'Z'))
+        && !ident[1..].is_empty()
+        && ident[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn parse_expr(&mut self) -> Result<Arc<Expr>, CationError> {
+        let mut terms = vec![self.parse_term()?];
+        while self.peek() == Some(&Token::Plus) {
+            self.advance();
+            terms.push(self.parse_term()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.into_iter().next().unwrap()
+        } else {
+            Arc::new(Expr::Sum(terms))
+        })
+    }
+
+    fn parse_term(&mut self) -> Result<Arc<Expr>, CationError> {
+        let mut factors = vec![self.parse_factor()?];
+        while self.peek() == Some(&Token::Star) {
+            self.advance();
+            factors.push(self.parse_factor()?);
+        }
+        Ok(if factors.len() == 1 {
+            factors.into_iter().next().unwrap()
+        } else {
+            Arc::new(Expr::Product(factors))
+        })
+    }
+
+    fn parse_factor(&mut self) -> Result<Arc<Expr>, CationError> {
+        match self.advance().cloned() {
+            Some(Token::Number(value)) => Ok(Arc::new(Expr::Scalar(value))),
+            Some(Token::Ident(ident)) if is_pauli_token(&ident) => {
+                Ok(Arc::new(Expr::Pauli(PauliString::from_string(&ident)?)))
+            }
+            Some(Token::Ident(ident)) => Ok(Arc::new(Expr::Symbol(Symbol::new(&ident)))),
+            Some(Token::LParen) => {
+                let inner = self.parse_expr()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err(CationError::Other("expected closing ')'".to_string())),
+                }
+            }
+            other => Err(CationError::Other(format!("expected a number, identifier, or '(', got {other:?}"))),
+        }
+    }
+}
+
+pub(crate) fn parse(input: &str) -> Result<Arc<Expr>, CationError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+    };
+    let expr = parser.parse_expr()?;
+    if parser.pos != tokens.len() {
+        return Err(CationError::Other(format!("unexpected trailing input at token {}", parser.pos)));
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_ir::pauli::Pauli;
+
+    #[test]
+    fn parses_scalar_times_pauli_plus_symbolic_sum() {
+        let parsed = parse("2.0 * X0 + theta * (Z1 + Z2)").unwrap();
+        assert_eq!(
+            parsed.as_ref(),
+            &Expr::Sum(vec![
+                Arc::new(Expr::Product(vec![
+                    Arc::new(Expr::Scalar(2.0)),
+                    Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)]))),
+                ])),
+                Arc::new(Expr::Product(vec![
+                    Arc::new(Expr::Symbol(Symbol::new("theta"))),
+                    Arc::new(Expr::Sum(vec![
+                        Arc::new(Expr::Pauli(PauliString::new([(1, Pauli::Z)]))),
+                        Arc::new(Expr::Pauli(PauliString::new([(2, Pauli::Z)]))),
+                    ])),
+                ])),
+            ])
+        );
+    }
+
+    #[test]
+    fn respects_precedence_and_left_associativity() {
+        let parsed = parse("a + b * c + d").unwrap();
+        let named = |n: &str| Arc::new(Expr::Symbol(Symbol::new(n)));
+        assert_eq!(
+            parsed.as_ref(),
+            &Expr::Sum(vec![
+                named("a"),
+                Arc::new(Expr::Product(vec![named("b"), named("c")])),
+                named("d"),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_parentheses() {
+        assert!(parse("(a + b").is_err());
+        assert!(parse("a + b)").is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Single-qubit Pauli operators.
+
+use std::fmt;
+
+use crate::error::CationError;
+
+use super::phase::Phase;
+
+/// A single-qubit Pauli operator, including the identity.
+///
+/// Variants are declared in the order `I < X < Y < Z`, so the derived
+/// `PartialOrd`/`Ord` give exactly that ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+impl Pauli {
+    /// Multiplies `self` by `other`, returning the phase picked up and the
+    /// resulting operator.
+    ///
+    /// For example `X * Y == (Phase::I, Z)` and `Y * X == (Phase::MinusI, Z)`.
+    pub fn mul(&self, other: &Pauli) -> (Phase, Pauli) {
+        use Pauli::*;
+        match (*self, *other) {
+            (I, p) => (Phase::One, p),
+            (p, I) => (Phase::One, p),
+            (p, q) if p == q => (Phase::One, I),
+            (X, Y) => (Phase::I, Z),
+            (Y, X) => (Phase::MinusI, Z),
+            (Y, Z) => (Phase::I, X),
+            (Z, Y) => (Phase::MinusI, X),
+            (Z, X) => (Phase::I, Y),
+            (X, Z) => (Phase::MinusI, Y),
+            _ => unreachable!("all Pauli pairs are covered above"),
+        }
+    }
+}
+
+impl TryFrom<char> for Pauli {
+    type Error = CationError;
+
+    /// Parses a single operator letter (`'I'`, `'X'`, `'Y'`, `'Z'`), as used
+    /// by interop formats like OpenFermion term dictionaries.
+    fn try_from(c: char) -> Result<Pauli, CationError> {
+        match c {
+            'I' => Ok(Pauli::I),
+            'X' => Ok(Pauli::X),
+            'Y' => Ok(Pauli::Y),
+            'Z' => Ok(Pauli::Z),
+            other => Err(CationError::InvalidPauliChar(other)),
+        }
+    }
+}
+
+impl fmt::Display for Pauli {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Pauli::I => "I",
+            Pauli::X => "X",
+            Pauli::Y => "Y",
+            Pauli::Z => "Z",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Pauli::{I as PI, X, Y, Z};
+    use Phase::{MinusI, One, I as PhI};
+
+    #[test]
+    fn multiplication_table() {
+        let table: [(Pauli, Pauli, Phase, Pauli); 16] = [
+            (PI, PI, One, PI),
+            (PI, X, One, X),
+            (PI, Y, One, Y),
+            (PI, Z, One, Z),
+            (X, PI, One, X),
+            (Y, PI, One, Y),
+            (Z, PI, One, Z),
+            (X, X, One, PI),
+            (Y, Y, One, PI),
+            (Z, Z, One, PI),
+            (X, Y, PhI, Z),
+            (Y, X, MinusI, Z),
+            (Y, Z, PhI, X),
+            (Z, Y, MinusI, X),
+            (Z, X, PhI, Y),
+            (X, Z, MinusI, Y),
+        ];
+        for (a, b, phase, prod) in table {
+            assert_eq!(a.mul(&b), (phase, prod), "{a:?} * {b:?}");
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(Pauli::I < Pauli::X);
+        let mut all = vec![Pauli::Z, Pauli::X, Pauli::I, Pauli::Y];
+        all.sort();
+        assert_eq!(all, vec![Pauli::I, Pauli::X, Pauli::Y, Pauli::Z]);
+    }
+
+    #[test]
+    fn try_from_char_parses_known_letters_and_rejects_others() {
+        assert_eq!(Pauli::try_from('I'), Ok(Pauli::I));
+        assert_eq!(Pauli::try_from('X'), Ok(Pauli::X));
+        assert_eq!(Pauli::try_from('Y'), Ok(Pauli::Y));
+        assert_eq!(Pauli::try_from('Z'), Ok(Pauli::Z));
+        assert!(Pauli::try_from('W').is_err());
+    }
+
+    #[test]
+    fn try_from_char_reports_the_offending_character() {
+        assert_eq!(Pauli::try_from('T'), Err(CationError::InvalidPauliChar('T')));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Pauli::I.to_string(), "I");
+        assert_eq!(Pauli::X.to_string(), "X");
+        assert_eq!(Pauli::Y.to_string(), "Y");
+        assert_eq!(Pauli::Z.to_string(), "Z");
+    }
+}
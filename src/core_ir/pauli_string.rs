@@ -0,0 +1,1119 @@
+//! Multi-qubit strings of single-qubit Pauli operators.
+
+use std::fmt;
+
+use bitvec::vec::BitVec;
+use smallvec::SmallVec;
+
+use crate::error::CationError;
+
+use super::pauli::Pauli;
+use super::phase::Phase;
+use super::sparse::SparseMatrix;
+
+/// A dense complex matrix, stored row-major as `(real, imaginary)` pairs.
+pub type DenseMatrix = Vec<Vec<(f64, f64)>>;
+
+/// The backing storage for [`PauliString`]: `(qubit, operator)` pairs kept
+/// sorted by qubit index. Most strings in practice act on only a handful
+/// of qubits, so the common case stays on the stack instead of heap
+/// allocating a map for every string.
+type Ops = SmallVec<[(usize, Pauli); 8]>;
+
+/// Inserts `(qubit, pauli)` into the sorted `ops`, returning the operator
+/// previously at `qubit` if one was already present (in which case it is
+/// overwritten, mirroring `BTreeMap::insert`'s return convention).
+fn insert_sorted(ops: &mut Ops, qubit: usize, pauli: Pauli) -> Option<Pauli> {
+    match ops.binary_search_by_key(&qubit, |&(q, _)| q) {
+        Ok(idx) => {
+            let previous = ops[idx].1;
+            ops[idx].1 = pauli;
+            Some(previous)
+        }
+        Err(idx) => {
+            ops.insert(idx, (qubit, pauli));
+            None
+        }
+    }
+}
+
+/// Looks up the operator at `qubit` in the sorted `ops`, if any.
+fn get_sorted(ops: &Ops, qubit: usize) -> Option<Pauli> {
+    ops.binary_search_by_key(&qubit, |&(q, _)| q)
+        .ok()
+        .map(|idx| ops[idx].1)
+}
+
+/// A tensor product of single-qubit Pauli operators acting on specific
+/// qubit indices, e.g. `X0 Z1` meaning `X` on qubit 0 and `Z` on qubit 1.
+///
+/// Qubits not mentioned are implicitly identity. The internal storage is
+/// kept sorted by qubit index and never stores identities, so two strings
+/// with the same non-identity support always compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PauliString {
+    ops: Ops,
+}
+
+// Serializes through the public `(qubit, Pauli)` pairs rather than deriving
+// directly on the private `ops` map, so the wire format is the same
+// sparse representation `new`/`iter` already expose.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PauliString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let ops: Vec<(usize, Pauli)> = self.iter().map(|(qubit, pauli)| (qubit, *pauli)).collect();
+        ops.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PauliString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ops = Vec::<(usize, Pauli)>::deserialize(deserializer)?;
+        PauliString::try_new(ops).map_err(serde::de::Error::custom)
+    }
+}
+
+impl fmt::Display for PauliString {
+    /// Renders as whitespace-separated `"{op}{qubit}"` tokens in index order,
+    /// matching the sparse notation [`PauliString::from_string`] parses; the
+    /// identity (no non-trivial qubits) renders as `"I0"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ops.is_empty() {
+            return write!(f, "I0");
+        }
+        let tokens: Vec<String> = self.ops.iter().map(|&(qubit, pauli)| format!("{pauli}{qubit}")).collect();
+        write!(f, "{}", tokens.join(" "))
+    }
+}
+
+impl PartialOrd for PauliString {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PauliString {
+    /// Orders lexicographically on `(qubit index, operator)` pairs, so two
+    /// strings that act on the same qubits with different operators never
+    /// compare equal.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.ops.iter().cmp(other.ops.iter())
+    }
+}
+
+impl PauliString {
+    /// Builds a `PauliString` from `(qubit, operator)` pairs, dropping any
+    /// explicit identities. Requires each qubit index to appear at most
+    /// once; use [`PauliString::new_with_phase`] if the same index may
+    /// appear more than once and should be collapsed via Pauli
+    /// multiplication instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the same qubit index appears twice. Use [`PauliString::try_new`]
+    /// to handle that case without panicking.
+    pub fn new(ops: impl IntoIterator<Item = (usize, Pauli)>) -> Self {
+        Self::try_new(ops).expect("duplicate qubit index in PauliString::new")
+    }
+
+    /// Builds a `PauliString` from `(qubit, operator)` pairs, dropping any
+    /// explicit identities, and returning an error if the same qubit index
+    /// is given more than once.
+    pub fn try_new(ops: impl IntoIterator<Item = (usize, Pauli)>) -> Result<Self, CationError> {
+        let mut sorted = Ops::new();
+        for (qubit, pauli) in ops {
+            if pauli == Pauli::I {
+                continue;
+            }
+            if insert_sorted(&mut sorted, qubit, pauli).is_some() {
+                return Err(CationError::DuplicateIndex(qubit, "in PauliString".to_string()));
+            }
+        }
+        Ok(PauliString { ops: sorted })
+    }
+
+    /// Builds a `PauliString` from `(qubit, operator)` pairs like
+    /// [`PauliString::new`], but instead of requiring unique qubit indices,
+    /// collapses a repeated index by multiplying its operators together
+    /// (dropping the result if it cancels to identity), accumulating the
+    /// total phase picked up along the way. Never panics or errors on a
+    /// duplicate index.
+    pub fn new_with_phase(ops: impl IntoIterator<Item = (usize, Pauli)>) -> (Phase, PauliString) {
+        let mut phase = Phase::One;
+        let mut sorted: Ops = Ops::new();
+        for (qubit, pauli) in ops {
+            if pauli == Pauli::I {
+                continue;
+            }
+            match sorted.binary_search_by_key(&qubit, |&(q, _)| q) {
+                Ok(idx) => {
+                    let (p, result) = sorted[idx].1.mul(&pauli);
+                    phase = phase * p;
+                    if result == Pauli::I {
+                        sorted.remove(idx);
+                    } else {
+                        sorted[idx].1 = result;
+                    }
+                }
+                Err(idx) => sorted.insert(idx, (qubit, pauli)),
+            }
+        }
+        (phase, PauliString { ops: sorted })
+    }
+
+    /// The identity operator, acting trivially on every qubit. Equivalent to
+    /// `PauliString::new([])`, but names the common special case so call
+    /// sites don't have to spell out an empty literal.
+    pub fn identity() -> PauliString {
+        PauliString { ops: Ops::new() }
+    }
+
+    /// Whether this is the identity operator, i.e. has no non-trivial
+    /// support. Equivalent to `self.weight() == 0`, but names the intent at
+    /// call sites that special-case the identity term in sums, traces, and
+    /// exports.
+    pub fn is_identity(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Multiplies `self` by `other` qubit-by-qubit, accumulating the total
+    /// phase and collapsing same-index operators. Qubits present in only
+    /// one operand are treated as identity in the other. The result is
+    /// re-canonicalized: sorted by index with identities dropped.
+    pub fn multiply(&self, other: &PauliString) -> (Phase, PauliString) {
+        let mut phase = Phase::One;
+        let mut ops = Ops::new();
+
+        let (mut i, mut j) = (0, 0);
+        while i < self.ops.len() || j < other.ops.len() {
+            let left = self.ops.get(i).copied();
+            let right = other.ops.get(j).copied();
+            let (qubit, a, b) = match (left, right) {
+                (Some((qa, pa)), Some((qb, pb))) => match qa.cmp(&qb) {
+                    std::cmp::Ordering::Less => {
+                        i += 1;
+                        (qa, pa, Pauli::I)
+                    }
+                    std::cmp::Ordering::Greater => {
+                        j += 1;
+                        (qb, Pauli::I, pb)
+                    }
+                    std::cmp::Ordering::Equal => {
+                        i += 1;
+                        j += 1;
+                        (qa, pa, pb)
+                    }
+                },
+                (Some((qa, pa)), None) => {
+                    i += 1;
+                    (qa, pa, Pauli::I)
+                }
+                (None, Some((qb, pb))) => {
+                    j += 1;
+                    (qb, Pauli::I, pb)
+                }
+                (None, None) => unreachable!(),
+            };
+            let (p, result) = a.mul(&b);
+            phase = phase * p;
+            if result != Pauli::I {
+                ops.push((qubit, result));
+            }
+        }
+
+        (phase, PauliString { ops })
+    }
+
+    /// Raises this string to the `n`-th power via repeated
+    /// [`PauliString::multiply`], accumulating whatever phase that picks
+    /// up rather than assuming `even -> identity, odd -> self` up front:
+    /// every qubit in `self` ends up multiplied by the same operator at
+    /// each step, and `Pauli::mul` always gives `(Phase::One, Pauli::I)`
+    /// for equal operands, so in practice the phase always comes out
+    /// `Phase::One` — but that falls out of the multiplication table
+    /// rather than being hardcoded. `pow(0)` is the identity.
+    pub fn pow(&self, n: u32) -> (Phase, PauliString) {
+        let mut phase = Phase::One;
+        let mut result = PauliString::identity();
+        for _ in 0..n {
+            let (p, product) = result.multiply(self);
+            phase = phase * p;
+            result = product;
+        }
+        (phase, result)
+    }
+
+    /// The number of qubits this string acts on non-trivially.
+    pub fn weight(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// The sorted qubit indices this string acts on non-trivially.
+    pub fn support(&self) -> Vec<usize> {
+        self.ops.iter().map(|&(qubit, _)| qubit).collect()
+    }
+
+    /// Iterates over the non-identity `(qubit, operator)` pairs in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Pauli)> {
+        self.ops.iter().map(|(qubit, pauli)| (*qubit, pauli))
+    }
+
+    /// The operator acting on `qubit`, or [`Pauli::I`] if it is unmentioned.
+    pub fn get(&self, qubit: usize) -> Pauli {
+        get_sorted(&self.ops, qubit).unwrap_or(Pauli::I)
+    }
+
+    /// Tensors `self` with `other`, merging their operators. Unlike
+    /// [`PauliString::multiply`], there is no phase and the supports must
+    /// be disjoint; overlapping qubits are an error rather than a collapse.
+    pub fn tensor(&self, other: &PauliString) -> Result<PauliString, CationError> {
+        let mut ops = self.ops.clone();
+        for &(qubit, pauli) in &other.ops {
+            if insert_sorted(&mut ops, qubit, pauli).is_some() {
+                return Err(CationError::DuplicateIndex(qubit, "in tensor product".to_string()));
+            }
+        }
+        Ok(PauliString { ops })
+    }
+
+    /// The dense `2^num_qubits x 2^num_qubits` complex matrix for this
+    /// string, built as the tensor product of the single-qubit Pauli
+    /// matrices (identity on unmentioned qubits). Qubit 0 is the most
+    /// significant tensor factor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_qubits` is smaller than one plus the highest qubit
+    /// index in the support.
+    pub fn to_dense_matrix(&self, num_qubits: usize) -> DenseMatrix {
+        if let Some(&(max_qubit, _)) = self.ops.last() {
+            assert!(
+                num_qubits > max_qubit,
+                "num_qubits {num_qubits} too small for support up to qubit {max_qubit}"
+            );
+        }
+
+        let mut matrix = vec![vec![(1.0, 0.0)]];
+        for qubit in 0..num_qubits {
+            matrix = kron(&matrix, &single_qubit_matrix(self.get(qubit)));
+        }
+        matrix
+    }
+
+    /// For basis index `col` treated as an input amplitude's index, returns
+    /// `(row, phase)`: the output row this operator maps `col` to, and the
+    /// complex value `M[row][col]` of the operator's matrix there. The
+    /// Y/Z phase is a function of `col`'s bits (the state being acted on),
+    /// not the output row's — shared by [`PauliString::to_sparse`] and
+    /// [`PauliString::apply`] so that convention lives in exactly one
+    /// place instead of being re-derived (and potentially re-broken).
+    fn flip_and_phase(&self, col: usize, num_qubits: usize) -> (usize, (f64, f64)) {
+        let mut row = col;
+        let mut phase = (1.0, 0.0);
+        for &(qubit, pauli) in self.ops.iter() {
+            let bit_pos = num_qubits - 1 - qubit;
+            let bit = (col >> bit_pos) & 1;
+            match pauli {
+                Pauli::I => {}
+                Pauli::X => row ^= 1 << bit_pos,
+                Pauli::Y => {
+                    row ^= 1 << bit_pos;
+                    let factor = if bit == 0 { (0.0, 1.0) } else { (0.0, -1.0) };
+                    phase = complex_mul(phase, factor);
+                }
+                Pauli::Z => {
+                    if bit == 1 {
+                        phase = complex_mul(phase, (-1.0, 0.0));
+                    }
+                }
+            }
+        }
+        (row, phase)
+    }
+
+    /// The sparse `2^num_qubits x 2^num_qubits` complex matrix for this
+    /// string, computed directly from bit-flip (X, Y) and sign (Y, Z) masks
+    /// in `O(2^num_qubits)` time and memory rather than by building the
+    /// dense tensor product. Every row has exactly one nonzero entry since
+    /// a Pauli string is a signed permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_qubits` is smaller than one plus the highest qubit
+    /// index in the support.
+    pub fn to_sparse(&self, num_qubits: usize) -> SparseMatrix {
+        if let Some(&(max_qubit, _)) = self.ops.last() {
+            assert!(
+                num_qubits > max_qubit,
+                "num_qubits {num_qubits} too small for support up to qubit {max_qubit}"
+            );
+        }
+
+        let dim = 1usize << num_qubits;
+        let mut col_indices = vec![0usize; dim];
+        let mut values = vec![(0.0, 0.0); dim];
+
+        // Each basis column `col` maps to exactly one nonzero row, so the
+        // loop is driven by the input column; filing the result under its
+        // *output* row (rather than under `col` itself) is what keeps this
+        // from silently transposing the matrix and flipping the sign of
+        // every off-diagonal `Y` entry.
+        for col in 0..dim {
+            let (row, phase) = self.flip_and_phase(col, num_qubits);
+            col_indices[row] = col;
+            values[row] = phase;
+        }
+
+        SparseMatrix {
+            num_rows: dim,
+            row_ptr: (0..=dim).collect(),
+            col_indices,
+            values,
+        }
+    }
+
+    /// Parses sparse notation like `"X0 Z2 Y5"` into a `PauliString`, where
+    /// each token is an operator letter followed by its qubit index and
+    /// tokens are whitespace-separated. The empty string parses to the
+    /// identity. Errors on an unknown operator letter, a malformed index,
+    /// or a duplicate index.
+    pub fn from_string(s: &str) -> Result<PauliString, CationError> {
+        let mut ops = Vec::new();
+        for token in s.split_whitespace() {
+            let (letter, index) = token.split_at(1);
+            let pauli = match letter {
+                "I" => Pauli::I,
+                "X" => Pauli::X,
+                "Y" => Pauli::Y,
+                "Z" => Pauli::Z,
+                other => return Err(CationError::Other(format!("unknown Pauli operator {other:?} in {token:?}"))),
+            };
+            let qubit: usize = index
+                .parse()
+                .map_err(|_| CationError::Other(format!("invalid qubit index in {token:?}")))?;
+            ops.push((qubit, pauli));
+        }
+        Self::try_new(ops)
+    }
+
+    /// Renders a compact sparse label like `"X0Z2"`: the same `"{op}{qubit}"`
+    /// tokens as [`PauliString::from_sparse_label`] parses, but concatenated
+    /// with no separator, so it's stable for logging or as a map key
+    /// independent of `Display`'s whitespace-joined, `"I0"`-for-identity
+    /// form. The identity renders as `"I"`.
+    pub fn to_sparse_label(&self) -> String {
+        if self.ops.is_empty() {
+            return "I".to_string();
+        }
+        self.ops.iter().map(|&(qubit, pauli)| format!("{pauli}{qubit}")).collect()
+    }
+
+    /// Parses the compact label [`PauliString::to_sparse_label`] produces:
+    /// operator letters directly followed by their qubit index, with no
+    /// separator between tokens (e.g. `"X0Z2"`), and the bare letter `"I"`
+    /// for the identity. Errors on an unknown operator letter, a malformed
+    /// index, or a duplicate index.
+    pub fn from_sparse_label(s: &str) -> Result<PauliString, CationError> {
+        if s == "I" {
+            return Ok(PauliString::identity());
+        }
+        let mut ops = Vec::new();
+        let mut chars = s.char_indices().peekable();
+        while let Some((start, letter)) = chars.next() {
+            let pauli = match letter {
+                'I' => Pauli::I,
+                'X' => Pauli::X,
+                'Y' => Pauli::Y,
+                'Z' => Pauli::Z,
+                other => return Err(CationError::Other(format!("unknown Pauli operator {other:?} in {s:?}"))),
+            };
+            let digits_start = start + letter.len_utf8();
+            while matches!(chars.peek(), Some((_, c)) if c.is_ascii_digit()) {
+                chars.next();
+            }
+            let digits_end = chars.peek().map(|&(i, _)| i).unwrap_or(s.len());
+            let qubit: usize = s[digits_start..digits_end]
+                .parse()
+                .map_err(|_| CationError::Other(format!("invalid qubit index in {s:?}")))?;
+            ops.push((qubit, pauli));
+        }
+        Self::try_new(ops)
+    }
+
+    /// Parses a Qiskit-style dense label like `"IXYZ"`, where the rightmost
+    /// character is qubit 0 (the opposite convention from
+    /// [`PauliString::from_string`]'s sparse notation). Errors on an unknown
+    /// operator letter.
+    pub fn from_qiskit_label(label: &str) -> Result<PauliString, CationError> {
+        let num_qubits = label.chars().count();
+        let mut ops = Vec::with_capacity(num_qubits);
+        for (position, c) in label.chars().enumerate() {
+            let pauli = Pauli::try_from(c)?;
+            ops.push((num_qubits - 1 - position, pauli));
+        }
+        Self::try_new(ops)
+    }
+
+    /// Applies `map` to every qubit index, re-canonicalizing the result.
+    /// Errors if `map` sends two distinct indices to the same index, since
+    /// that would silently merge two operators. A common use is
+    /// `relabel(|i| i + offset)` to embed a block into a larger system.
+    pub fn relabel(&self, map: impl Fn(usize) -> usize) -> Result<PauliString, CationError> {
+        let mut ops = Ops::new();
+        for &(qubit, pauli) in &self.ops {
+            let mapped = map(qubit);
+            if insert_sorted(&mut ops, mapped, pauli).is_some() {
+                return Err(CationError::DuplicateIndex(mapped, "from relabeling".to_string()));
+            }
+        }
+        Ok(PauliString { ops })
+    }
+
+    /// Whether `self` and `other` are qubit-wise commuting: on every qubit
+    /// where both act non-trivially they carry the same operator. This is
+    /// stricter than [`PauliString::commutes_with`] (which only needs an
+    /// even number of disagreements) but means the two strings can be
+    /// measured in the same basis.
+    pub fn qubit_wise_commutes_with(&self, other: &PauliString) -> bool {
+        self.ops
+            .iter()
+            .all(|&(qubit, pauli)| get_sorted(&other.ops, qubit).is_none_or(|o| o == pauli))
+    }
+
+    /// The expectation value `⟨ψ|P|ψ⟩` of this operator over a complex
+    /// statevector `state` of length `2^num_qubits`, computed by applying
+    /// `P` via [`PauliString::to_sparse`]'s one-nonzero-per-row structure
+    /// rather than materializing the full matrix. Errors if `state`'s length
+    /// isn't `2^num_qubits` or is too small for this string's support.
+    pub fn expectation(&self, state: &[(f64, f64)], num_qubits: usize) -> Result<(f64, f64), CationError> {
+        let dim = 1usize << num_qubits;
+        if state.len() != dim {
+            return Err(CationError::DimensionMismatch(format!(
+                "state length {} does not match 2^num_qubits = {dim}",
+                state.len()
+            )));
+        }
+        if let Some(&(max_qubit, _)) = self.ops.last() {
+            if max_qubit >= num_qubits {
+                return Err(CationError::DimensionMismatch(format!(
+                    "num_qubits {num_qubits} too small for support up to qubit {max_qubit}"
+                )));
+            }
+        }
+
+        let sparse = self.to_sparse(num_qubits);
+        let mut total = (0.0, 0.0);
+        for row in 0..dim {
+            let col = sparse.col_indices[row];
+            let applied = complex_mul(sparse.values[row], state[col]);
+            let conj_row = (state[row].0, -state[row].1);
+            let contribution = complex_mul(conj_row, applied);
+            total = (total.0 + contribution.0, total.1 + contribution.1);
+        }
+        Ok(total)
+    }
+
+    /// Applies this operator to `state` in place: a complex statevector of
+    /// length `2^num_qubits`. Like [`PauliString::expectation`], this never
+    /// materializes a matrix, dense or sparse — `X`/`Y` operators flip a
+    /// fixed set of bits in the basis-state index (so applying the same
+    /// flip twice is its own inverse), and `Y`/`Z` operators multiply in a
+    /// sign or `i`-factor that depends on the bit's value *before* the
+    /// flip, i.e. the amplitude being read from, via the same convention
+    /// [`PauliString::to_sparse`] uses internally. Walking only the
+    /// `row < row ^ flip_mask` half of the basis swaps each
+    /// amplitude pair exactly once, with no auxiliary buffer. Errors if
+    /// `state`'s length isn't `2^num_qubits` or is too small for this
+    /// string's support.
+    pub fn apply(&self, state: &mut [(f64, f64)], num_qubits: usize) -> Result<(), CationError> {
+        let dim = 1usize << num_qubits;
+        if state.len() != dim {
+            return Err(CationError::DimensionMismatch(format!(
+                "state length {} does not match 2^num_qubits = {dim}",
+                state.len()
+            )));
+        }
+        if let Some(&(max_qubit, _)) = self.ops.last() {
+            if max_qubit >= num_qubits {
+                return Err(CationError::DimensionMismatch(format!(
+                    "num_qubits {num_qubits} too small for support up to qubit {max_qubit}"
+                )));
+            }
+        }
+
+        let mut flip_mask = 0usize;
+        for &(qubit, pauli) in &self.ops {
+            if matches!(pauli, Pauli::X | Pauli::Y) {
+                flip_mask |= 1 << (num_qubits - 1 - qubit);
+            }
+        }
+
+        for row in 0..dim {
+            let col = row ^ flip_mask;
+            if col < row {
+                continue;
+            }
+            if col == row {
+                let (_, phase) = self.flip_and_phase(row, num_qubits);
+                state[row] = complex_mul(phase, state[row]);
+            } else {
+                let (_, phase_row_from_col) = self.flip_and_phase(col, num_qubits);
+                let (_, phase_col_from_row) = self.flip_and_phase(row, num_qubits);
+                let new_row = complex_mul(phase_row_from_col, state[col]);
+                let new_col = complex_mul(phase_col_from_row, state[row]);
+                state[row] = new_row;
+                state[col] = new_col;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `self` and `other` commute, computed as the symplectic inner
+    /// product of their [`PauliString::to_symplectic`] forms: two strings
+    /// anticommute on a shared qubit whenever exactly one of the X/Z bit
+    /// pairs disagrees there, and the overall strings commute iff an even
+    /// number of qubits anticommute.
+    pub fn commutes_with(&self, other: &PauliString) -> bool {
+        let num_qubits = self
+            .ops
+            .last()
+            .map(|&(q, _)| q)
+            .into_iter()
+            .chain(other.ops.last().map(|&(q, _)| q))
+            .max()
+            .map_or(0, |q| q + 1);
+
+        let (xa, za) = self.to_symplectic(num_qubits);
+        let (xb, zb) = other.to_symplectic(num_qubits);
+
+        let mut parity = false;
+        for i in 0..num_qubits {
+            parity ^= (xa[i] && zb[i]) ^ (za[i] && xb[i]);
+        }
+        !parity
+    }
+
+    /// Whether `self` and `other` anticommute, i.e. `!self.commutes_with(other)`.
+    /// Defined directly in terms of the same odd-overlap-count parity rather
+    /// than negating at call sites, so identity overlaps (which always
+    /// contribute an even, commuting disagreement count) are handled
+    /// correctly without the caller having to reason about it.
+    pub fn anticommutes_with(&self, other: &PauliString) -> bool {
+        !self.commutes_with(other)
+    }
+
+    /// Converts to the symplectic (X-bits, Z-bits) representation over
+    /// `num_qubits` qubits: bit `i` of the first vector is set iff this
+    /// string has an X component on qubit `i` (i.e. `X` or `Y`), and bit `i`
+    /// of the second is set iff it has a Z component (`Z` or `Y`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_qubits` is smaller than one plus the highest qubit
+    /// index in the support.
+    pub fn to_symplectic(&self, num_qubits: usize) -> (BitVec, BitVec) {
+        if let Some(&(max_qubit, _)) = self.ops.last() {
+            assert!(
+                num_qubits > max_qubit,
+                "num_qubits {num_qubits} too small for support up to qubit {max_qubit}"
+            );
+        }
+
+        let mut x = BitVec::repeat(false, num_qubits);
+        let mut z = BitVec::repeat(false, num_qubits);
+        for &(qubit, pauli) in &self.ops {
+            match pauli {
+                Pauli::I => {}
+                Pauli::X => x.set(qubit, true),
+                Pauli::Y => {
+                    x.set(qubit, true);
+                    z.set(qubit, true);
+                }
+                Pauli::Z => z.set(qubit, true),
+            }
+        }
+        (x, z)
+    }
+
+    /// Builds a `PauliString` from the symplectic (X-bits, Z-bits)
+    /// representation produced by [`PauliString::to_symplectic`]: a qubit
+    /// with both bits set becomes `Y`, X-only becomes `X`, Z-only becomes
+    /// `Z`, and neither becomes (implicit) identity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` and `z` have different lengths.
+    pub fn from_symplectic(x: &BitVec, z: &BitVec) -> PauliString {
+        assert_eq!(x.len(), z.len(), "symplectic X/Z vectors must have the same length");
+
+        let mut ops = Ops::new();
+        for qubit in 0..x.len() {
+            let pauli = match (x[qubit], z[qubit]) {
+                (false, false) => continue,
+                (true, false) => Pauli::X,
+                (false, true) => Pauli::Z,
+                (true, true) => Pauli::Y,
+            };
+            ops.push((qubit, pauli));
+        }
+        PauliString { ops }
+    }
+}
+
+fn single_qubit_matrix(pauli: Pauli) -> DenseMatrix {
+    match pauli {
+        Pauli::I => vec![vec![(1.0, 0.0), (0.0, 0.0)], vec![(0.0, 0.0), (1.0, 0.0)]],
+        Pauli::X => vec![vec![(0.0, 0.0), (1.0, 0.0)], vec![(1.0, 0.0), (0.0, 0.0)]],
+        Pauli::Y => vec![vec![(0.0, 0.0), (0.0, -1.0)], vec![(0.0, 1.0), (0.0, 0.0)]],
+        Pauli::Z => vec![vec![(1.0, 0.0), (0.0, 0.0)], vec![(0.0, 0.0), (-1.0, 0.0)]],
+    }
+}
+
+fn complex_mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn kron(a: &[Vec<(f64, f64)>], b: &[Vec<(f64, f64)>]) -> DenseMatrix {
+    let (ra, ca) = (a.len(), a[0].len());
+    let (rb, cb) = (b.len(), b[0].len());
+    let mut out = vec![vec![(0.0, 0.0); ca * cb]; ra * rb];
+    for i in 0..ra {
+        for j in 0..ca {
+            for k in 0..rb {
+                for l in 0..cb {
+                    out[i * rb + k][j * cb + l] = complex_mul(a[i][j], b[k][l]);
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Pauli::{X, Y, Z};
+
+    #[test]
+    fn multiply_example() {
+        let a = PauliString::new([(0, X), (1, Z)]);
+        let b = PauliString::new([(0, Y)]);
+        let (phase, product) = a.multiply(&b);
+        assert_eq!(phase, Phase::I);
+        assert_eq!(product, PauliString::new([(0, Z), (1, Z)]));
+    }
+
+    #[test]
+    fn pow_of_even_exponent_is_identity() {
+        let x0z1 = PauliString::new([(0, X), (1, Z)]);
+        assert_eq!(x0z1.pow(2), (Phase::One, PauliString::identity()));
+    }
+
+    #[test]
+    fn pow_of_odd_exponent_is_the_original_string() {
+        let x0z1 = PauliString::new([(0, X), (1, Z)]);
+        assert_eq!(x0z1.pow(3), (Phase::One, x0z1));
+    }
+
+    #[test]
+    fn pow_of_zero_is_identity() {
+        let x0 = PauliString::new([(0, X)]);
+        assert_eq!(x0.pow(0), (Phase::One, PauliString::identity()));
+    }
+
+    #[test]
+    fn identity_law() {
+        let a = PauliString::new([(0, X), (2, Y)]);
+        let identity = PauliString::new([]);
+        let (phase, product) = a.multiply(&identity);
+        assert_eq!(phase, Phase::One);
+        assert_eq!(product, a);
+    }
+
+    #[test]
+    fn associativity_on_random_triples() {
+        let triples = [
+            (
+                PauliString::new([(0, X), (1, Y)]),
+                PauliString::new([(0, Z), (2, X)]),
+                PauliString::new([(1, Z), (2, Y)]),
+            ),
+            (
+                PauliString::new([(0, Y)]),
+                PauliString::new([(0, Y), (1, Z)]),
+                PauliString::new([(1, X), (3, X)]),
+            ),
+            (
+                PauliString::new([(5, X), (6, Z)]),
+                PauliString::new([(5, Z)]),
+                PauliString::new([(6, X), (7, Y)]),
+            ),
+        ];
+        for (a, b, c) in triples {
+            let (phase_ab, ab) = a.multiply(&b);
+            let (phase_ab_c, ab_c) = ab.multiply(&c);
+            let (phase_bc, bc) = b.multiply(&c);
+            let (phase_a_bc, a_bc) = a.multiply(&bc);
+            assert_eq!(ab_c, a_bc);
+            assert_eq!(phase_ab * phase_ab_c, phase_bc * phase_a_bc);
+        }
+    }
+
+    #[test]
+    fn commutation() {
+        let x0 = PauliString::new([(0, X)]);
+        let x1 = PauliString::new([(1, X)]);
+        let z0 = PauliString::new([(0, Z)]);
+        assert!(x0.commutes_with(&x1));
+        assert!(!x0.commutes_with(&z0));
+
+        let x0x1 = PauliString::new([(0, X), (1, X)]);
+        let z0z1 = PauliString::new([(0, Z), (1, Z)]);
+        assert!(x0x1.commutes_with(&z0z1));
+    }
+
+    #[test]
+    fn ordering_distinguishes_operator_type() {
+        use std::collections::BTreeSet;
+
+        let x0 = PauliString::new([(0, X)]);
+        let z0 = PauliString::new([(0, Z)]);
+        assert_ne!(x0.cmp(&z0), std::cmp::Ordering::Equal);
+
+        let set: BTreeSet<PauliString> = [x0, z0].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn try_new_rejects_duplicate_index() {
+        assert!(PauliString::try_new([(0, X), (0, Z)]).is_err());
+        assert!(PauliString::try_new([(0, X), (1, Z)]).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate qubit index")]
+    fn new_panics_on_duplicate_index() {
+        PauliString::new([(0, X), (0, Z)]);
+    }
+
+    #[test]
+    fn new_with_phase_collapses_duplicate_index_via_pauli_multiplication() {
+        use Phase::I as PhaseI;
+
+        let (phase, ps) = PauliString::new_with_phase([(0, X), (0, Y)]);
+        assert_eq!(phase, PhaseI);
+        assert_eq!(ps, PauliString::new([(0, Z)]));
+    }
+
+    #[test]
+    fn new_with_phase_cancels_identical_operators_to_identity() {
+        let (phase, ps) = PauliString::new_with_phase([(0, X), (0, X)]);
+        assert_eq!(phase, Phase::One);
+        assert!(ps.is_identity());
+    }
+
+    #[test]
+    fn weight_and_support() {
+        let s = PauliString::new([(0, X), (2, Z)]);
+        assert_eq!(s.weight(), 2);
+        assert_eq!(s.support(), vec![0, 2]);
+
+        let identity = PauliString::new([]);
+        assert_eq!(identity.weight(), 0);
+        assert!(identity.support().is_empty());
+    }
+
+    #[test]
+    fn iter_and_get() {
+        let s = PauliString::new([(0, X), (2, Z)]);
+        let items: Vec<(usize, Pauli)> = s.iter().map(|(q, p)| (q, *p)).collect();
+        assert_eq!(items, vec![(0, X), (2, Z)]);
+        assert_eq!(s.get(1), Pauli::I);
+    }
+
+    #[test]
+    fn tensor_disjoint_succeeds() {
+        let a = PauliString::new([(0, X)]);
+        let b = PauliString::new([(1, Z)]);
+        let product = a.tensor(&b).unwrap();
+        assert_eq!(product, PauliString::new([(0, X), (1, Z)]));
+    }
+
+    #[test]
+    fn tensor_overlapping_fails() {
+        let a = PauliString::new([(0, X)]);
+        let b = PauliString::new([(0, Z)]);
+        assert!(a.tensor(&b).is_err());
+    }
+
+    #[test]
+    fn tensor_with_identity_is_noop() {
+        let a = PauliString::new([(0, X), (2, Y)]);
+        let identity = PauliString::new([]);
+        assert_eq!(a.tensor(&identity).unwrap(), a);
+    }
+
+    #[test]
+    fn relabel_offset() {
+        let a = PauliString::new([(0, X), (1, Z)]);
+        let shifted = a.relabel(|i| i + 10).unwrap();
+        assert_eq!(shifted, PauliString::new([(10, X), (11, Z)]));
+    }
+
+    #[test]
+    fn relabel_collision_rejected() {
+        let a = PauliString::new([(0, X), (1, Z)]);
+        assert!(a.relabel(|_| 5).is_err());
+    }
+
+    #[test]
+    fn display_round_trips_through_from_string() {
+        let s = PauliString::new([(0, X), (2, Z), (5, Y)]);
+        assert_eq!(s.to_string(), "X0 Z2 Y5");
+        assert_eq!(PauliString::from_string(&s.to_string()).unwrap(), s);
+
+        let identity = PauliString::new([]);
+        assert_eq!(identity.to_string(), "I0");
+    }
+
+    #[test]
+    fn from_string_parses_sparse_notation() {
+        let s = PauliString::from_string("X0 Z2 Y5").unwrap();
+        assert_eq!(s, PauliString::new([(0, X), (2, Z), (5, Y)]));
+    }
+
+    #[test]
+    fn from_string_empty_is_identity() {
+        let s = PauliString::from_string("").unwrap();
+        assert_eq!(s, PauliString::new([]));
+    }
+
+    #[test]
+    fn from_string_rejects_bad_input() {
+        assert!(PauliString::from_string("W0").is_err());
+        assert!(PauliString::from_string("Xa").is_err());
+        assert!(PauliString::from_string("X0 Z0").is_err());
+    }
+
+    #[test]
+    fn to_sparse_label_round_trips_through_from_sparse_label() {
+        let s = PauliString::new([(0, X), (2, Z), (5, Y)]);
+        assert_eq!(s.to_sparse_label(), "X0Z2Y5");
+        assert_eq!(PauliString::from_sparse_label(&s.to_sparse_label()).unwrap(), s);
+    }
+
+    #[test]
+    fn sparse_label_renders_identity_as_bare_i() {
+        let identity = PauliString::identity();
+        assert_eq!(identity.to_sparse_label(), "I");
+        assert_eq!(PauliString::from_sparse_label("I").unwrap(), identity);
+    }
+
+    #[test]
+    fn from_sparse_label_rejects_bad_input() {
+        assert!(PauliString::from_sparse_label("W0").is_err());
+        assert!(PauliString::from_sparse_label("Xa").is_err());
+        assert!(PauliString::from_sparse_label("X0Z0").is_err());
+    }
+
+    #[test]
+    fn from_qiskit_label_treats_rightmost_char_as_qubit_zero() {
+        let s = PauliString::from_qiskit_label("IIX").unwrap();
+        assert_eq!(s, PauliString::new([(0, X)]));
+
+        let s = PauliString::from_qiskit_label("XII").unwrap();
+        assert_eq!(s, PauliString::new([(2, X)]));
+    }
+
+    #[test]
+    fn from_qiskit_label_rejects_unknown_letter() {
+        assert!(PauliString::from_qiskit_label("IWI").is_err());
+    }
+
+    #[test]
+    fn qubit_wise_commutes_requires_matching_shared_operators() {
+        let x0x1 = PauliString::new([(0, X), (1, X)]);
+        let x0 = PauliString::new([(0, X)]);
+        let z0 = PauliString::new([(0, Z)]);
+        assert!(x0x1.qubit_wise_commutes_with(&x0));
+        assert!(!x0x1.qubit_wise_commutes_with(&z0));
+    }
+
+    #[test]
+    fn expectation_of_x_on_plus_state_is_one() {
+        let x0 = PauliString::new([(0, X)]);
+        let inv_sqrt2 = std::f64::consts::FRAC_1_SQRT_2;
+        let plus = [(inv_sqrt2, 0.0), (inv_sqrt2, 0.0)];
+        let (real, imag) = x0.expectation(&plus, 1).unwrap();
+        assert!((real - 1.0).abs() < 1e-12);
+        assert!(imag.abs() < 1e-12);
+    }
+
+    #[test]
+    fn expectation_rejects_mismatched_state_length() {
+        let x0 = PauliString::new([(0, X)]);
+        assert!(x0.expectation(&[(1.0, 0.0)], 1).is_err());
+    }
+
+    #[test]
+    fn expectation_of_y_on_y_eigenstate_is_one() {
+        // (|0> + i|1>) / sqrt(2) is the +1 eigenstate of Y.
+        let y0 = PauliString::new([(0, Y)]);
+        let inv_sqrt2 = std::f64::consts::FRAC_1_SQRT_2;
+        let state = [(inv_sqrt2, 0.0), (0.0, inv_sqrt2)];
+        let (real, imag) = y0.expectation(&state, 1).unwrap();
+        assert!((real - 1.0).abs() < 1e-12);
+        assert!(imag.abs() < 1e-12);
+    }
+
+    #[test]
+    fn apply_x_swaps_the_two_amplitudes() {
+        let x0 = PauliString::new([(0, X)]);
+        let mut state = [(1.0, 0.0), (0.0, 0.0)];
+        x0.apply(&mut state, 1).unwrap();
+        assert_eq!(state, [(0.0, 0.0), (1.0, 0.0)]);
+    }
+
+    #[test]
+    fn apply_z_negates_the_one_amplitude() {
+        let z0 = PauliString::new([(0, Z)]);
+        let mut state = [(1.0, 0.0), (1.0, 0.0)];
+        z0.apply(&mut state, 1).unwrap();
+        assert_eq!(state, [(1.0, 0.0), (-1.0, 0.0)]);
+    }
+
+    #[test]
+    fn apply_y_maps_zero_to_i_one_and_one_to_minus_i_zero() {
+        let y0 = PauliString::new([(0, Y)]);
+        let mut zero_state = [(1.0, 0.0), (0.0, 0.0)];
+        y0.apply(&mut zero_state, 1).unwrap();
+        assert_eq!(zero_state, [(0.0, 0.0), (0.0, 1.0)]);
+
+        let mut one_state = [(0.0, 0.0), (1.0, 0.0)];
+        y0.apply(&mut one_state, 1).unwrap();
+        assert_eq!(one_state, [(0.0, -1.0), (0.0, 0.0)]);
+    }
+
+    #[test]
+    fn apply_matches_expectation_for_a_random_pauli() {
+        let x0z1 = PauliString::new([(0, X), (1, Z)]);
+        let state = [(0.5, 0.0), (0.5, 0.0), (0.5, 0.0), (0.5, 0.0)];
+        let mut applied = state;
+        x0z1.apply(&mut applied, 2).unwrap();
+
+        let mut conj_dot = (0.0, 0.0);
+        for (s, a) in state.iter().zip(applied.iter()) {
+            let conj_s = (s.0, -s.1);
+            let contribution = complex_mul(conj_s, *a);
+            conj_dot = (conj_dot.0 + contribution.0, conj_dot.1 + contribution.1);
+        }
+        let expected = x0z1.expectation(&state, 2).unwrap();
+        assert!((conj_dot.0 - expected.0).abs() < 1e-12);
+        assert!((conj_dot.1 - expected.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn apply_rejects_mismatched_state_length() {
+        let x0 = PauliString::new([(0, X)]);
+        assert!(x0.apply(&mut [(1.0, 0.0)], 1).is_err());
+    }
+
+    #[test]
+    fn dense_matrix_single_qubit_z() {
+        let z0 = PauliString::new([(0, Z)]);
+        let matrix = z0.to_dense_matrix(1);
+        assert_eq!(
+            matrix,
+            vec![
+                vec![(1.0, 0.0), (0.0, 0.0)],
+                vec![(0.0, 0.0), (-1.0, 0.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn dense_matrix_x0_x1_is_permutation() {
+        let xx = PauliString::new([(0, X), (1, X)]);
+        let matrix = xx.to_dense_matrix(2);
+        let expected = vec![
+            vec![(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (1.0, 0.0)],
+            vec![(0.0, 0.0), (0.0, 0.0), (1.0, 0.0), (0.0, 0.0)],
+            vec![(0.0, 0.0), (1.0, 0.0), (0.0, 0.0), (0.0, 0.0)],
+            vec![(1.0, 0.0), (0.0, 0.0), (0.0, 0.0), (0.0, 0.0)],
+        ];
+        assert_eq!(matrix, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "too small")]
+    fn dense_matrix_panics_on_too_few_qubits() {
+        let z2 = PauliString::new([(2, Z)]);
+        z2.to_dense_matrix(2);
+    }
+
+    #[test]
+    fn symplectic_round_trip() {
+        let s = PauliString::new([(0, X), (1, Y), (2, Z)]);
+        let (x, z) = s.to_symplectic(3);
+        assert_eq!(x.iter().map(|b| *b).collect::<Vec<_>>(), vec![true, true, false]);
+        assert_eq!(z.iter().map(|b| *b).collect::<Vec<_>>(), vec![false, true, true]);
+        assert_eq!(PauliString::from_symplectic(&x, &z), s);
+    }
+
+    #[test]
+    fn commutes_with_matches_symplectic_inner_product() {
+        let x0 = PauliString::new([(0, X)]);
+        let z0 = PauliString::new([(0, Z)]);
+        let x0x1 = PauliString::new([(0, X), (1, X)]);
+        let z0z1 = PauliString::new([(0, Z), (1, Z)]);
+        assert!(!x0.commutes_with(&z0));
+        assert!(x0x1.commutes_with(&z0z1));
+    }
+
+    #[test]
+    fn anticommutes_with_matches_odd_overlap_count() {
+        let x0 = PauliString::new([(0, X)]);
+        let y0 = PauliString::new([(0, Y)]);
+        let x1 = PauliString::new([(1, X)]);
+        assert!(x0.anticommutes_with(&y0));
+        assert!(!x0.anticommutes_with(&x1));
+    }
+
+    #[test]
+    fn identity_constructor_is_identity_and_displays_as_i0() {
+        let identity = PauliString::identity();
+        assert!(identity.is_identity());
+        // Display's existing identity rendering is "I0" (an index-0 token,
+        // matching the `from_string` grammar), not bare "I".
+        assert_eq!(identity.to_string(), "I0");
+        assert_eq!(identity, PauliString::new([]));
+    }
+
+    #[test]
+    fn is_identity_is_false_for_non_trivial_support() {
+        let x0 = PauliString::new([(0, X)]);
+        assert!(!x0.is_identity());
+    }
+
+    #[test]
+    fn sparse_matches_dense_nonzero_pattern() {
+        let s = PauliString::new([(0, X), (1, Z)]);
+        let dense = s.to_dense_matrix(2);
+        let sparse = s.to_sparse(2);
+
+        assert_eq!(sparse.num_rows, 4);
+        for (row, dense_row) in dense.iter().enumerate() {
+            let nonzeros: Vec<(usize, (f64, f64))> = dense_row
+                .iter()
+                .enumerate()
+                .filter(|(_, &v)| v != (0.0, 0.0))
+                .map(|(col, &v)| (col, v))
+                .collect();
+            assert_eq!(nonzeros.len(), 1);
+            let (col, value) = nonzeros[0];
+            assert_eq!(sparse.col_indices[row], col);
+            assert_eq!(sparse.values[row], value);
+        }
+    }
+}
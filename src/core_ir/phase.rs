@@ -0,0 +1,110 @@
+//! Exact powers-of-`i` phase factors produced by Pauli algebra.
+
+use std::fmt;
+use std::ops::Mul;
+
+/// A phase factor that is a power of `i`: `1`, `i`, `-1`, or `-i`.
+///
+/// Pauli multiplication never produces any other phase, so keeping it as
+/// an exact enum (rather than a complex float) avoids floating point error
+/// creeping into algebraic simplification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    One,
+    I,
+    MinusOne,
+    MinusI,
+}
+
+impl Phase {
+    /// The complex conjugate: `i` and `-i` swap, `1` and `-1` are unchanged.
+    pub fn conjugate(self) -> Phase {
+        match self {
+            Phase::One => Phase::One,
+            Phase::I => Phase::MinusI,
+            Phase::MinusOne => Phase::MinusOne,
+            Phase::MinusI => Phase::I,
+        }
+    }
+
+    /// The `(real, imaginary)` parts of this phase as a complex number.
+    pub fn to_complex(self) -> (f64, f64) {
+        match self {
+            Phase::One => (1.0, 0.0),
+            Phase::I => (0.0, 1.0),
+            Phase::MinusOne => (-1.0, 0.0),
+            Phase::MinusI => (0.0, -1.0),
+        }
+    }
+}
+
+impl Mul for Phase {
+    type Output = Phase;
+
+    /// Composes two phases as powers of `i`, e.g. `I * I == MinusOne`.
+    fn mul(self, rhs: Phase) -> Phase {
+        // Represent each phase as its exponent in {0, 1, 2, 3} and add mod 4.
+        let exponent = |p: Phase| match p {
+            Phase::One => 0,
+            Phase::I => 1,
+            Phase::MinusOne => 2,
+            Phase::MinusI => 3,
+        };
+        match (exponent(self) + exponent(rhs)) % 4 {
+            0 => Phase::One,
+            1 => Phase::I,
+            2 => Phase::MinusOne,
+            3 => Phase::MinusI,
+            _ => unreachable!("mod 4 is always in 0..4"),
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Phase::One => "1",
+            Phase::I => "i",
+            Phase::MinusOne => "-1",
+            Phase::MinusI => "-i",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composition() {
+        assert_eq!(Phase::I * Phase::I, Phase::MinusOne);
+        assert_eq!(Phase::I * Phase::MinusI, Phase::One);
+        assert_eq!(Phase::MinusOne * Phase::MinusOne, Phase::One);
+        assert_eq!(Phase::One * Phase::MinusI, Phase::MinusI);
+    }
+
+    #[test]
+    fn conjugate() {
+        assert_eq!(Phase::I.conjugate(), Phase::MinusI);
+        assert_eq!(Phase::MinusI.conjugate(), Phase::I);
+        assert_eq!(Phase::One.conjugate(), Phase::One);
+        assert_eq!(Phase::MinusOne.conjugate(), Phase::MinusOne);
+    }
+
+    #[test]
+    fn complex_parts() {
+        assert_eq!(Phase::One.to_complex(), (1.0, 0.0));
+        assert_eq!(Phase::I.to_complex(), (0.0, 1.0));
+        assert_eq!(Phase::MinusOne.to_complex(), (-1.0, 0.0));
+        assert_eq!(Phase::MinusI.to_complex(), (0.0, -1.0));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Phase::One.to_string(), "1");
+        assert_eq!(Phase::I.to_string(), "i");
+        assert_eq!(Phase::MinusOne.to_string(), "-1");
+        assert_eq!(Phase::MinusI.to_string(), "-i");
+    }
+}
@@ -0,0 +1,13 @@
+//! A minimal CSR-style sparse complex matrix.
+
+/// A complex matrix in compressed sparse row form.
+///
+/// `row_ptr` has `num_rows + 1` entries; the nonzero entries of row `i` are
+/// `col_indices[row_ptr[i]..row_ptr[i + 1]]` with matching `values`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+    pub num_rows: usize,
+    pub row_ptr: Vec<usize>,
+    pub col_indices: Vec<usize>,
+    pub values: Vec<(f64, f64)>,
+}
@@ -0,0 +1,279 @@
+//! Free and bound parameters used inside symbolic expressions.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// The process-wide table of interned symbol names. Large Hamiltonians
+/// repeat the same parameter name across thousands of terms; interning
+/// lets them all share one `Arc<str>` allocation and compare by pointer
+/// instead of by byte content in the common case.
+fn interner() -> &'static Mutex<HashSet<Arc<str>>> {
+    static TABLE: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+    TABLE.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+fn intern(name: &str) -> Arc<str> {
+    let mut table = interner().lock().unwrap();
+    if let Some(existing) = table.get(name) {
+        return existing.clone();
+    }
+    let arc: Arc<str> = Arc::from(name);
+    table.insert(arc.clone());
+    arc
+}
+
+/// A named parameter, optionally bound to a numeric value.
+///
+/// `Named` and `Bound` are always distinct even when they share a name:
+/// binding is an explicit act, not something structural equality should
+/// paper over. Names are interned (see [`Symbol::new`]), so two symbols
+/// with the same name share one allocation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Symbol {
+    Named(Arc<str>),
+    Bound { name: Arc<str>, value: f64 },
+    /// Like `Bound`, but for parameters whose value is genuinely complex
+    /// (e.g. a phase), kept as a distinct variant so the common real-valued
+    /// `Bound` path (and anything matching on it) is unaffected.
+    BoundComplex { name: Arc<str>, re: f64, im: f64 },
+}
+
+impl Eq for Symbol {}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    /// Orders `Named` before `Bound` before `BoundComplex`, then by name,
+    /// then (for two values of the same variant with the same name) by
+    /// value via `total_cmp` so `NaN` has a defined position instead of
+    /// comparing unordered.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        fn rank(s: &Symbol) -> u8 {
+            match s {
+                Symbol::Named(_) => 0,
+                Symbol::Bound { .. } => 1,
+                Symbol::BoundComplex { .. } => 2,
+            }
+        }
+        match (self, other) {
+            (Symbol::Named(a), Symbol::Named(b)) => a.cmp(b),
+            (
+                Symbol::Bound { name: n1, value: v1 },
+                Symbol::Bound { name: n2, value: v2 },
+            ) => n1.cmp(n2).then_with(|| v1.total_cmp(v2)),
+            (
+                Symbol::BoundComplex { name: n1, re: re1, im: im1 },
+                Symbol::BoundComplex { name: n2, re: re2, im: im2 },
+            ) => n1.cmp(n2).then_with(|| re1.total_cmp(re2)).then_with(|| im1.total_cmp(im2)),
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Symbol::Named(name) => {
+                0u8.hash(state);
+                name.hash(state);
+            }
+            Symbol::Bound { name, value } => {
+                1u8.hash(state);
+                name.hash(state);
+                value.to_bits().hash(state);
+            }
+            Symbol::BoundComplex { name, re, im } => {
+                2u8.hash(state);
+                name.hash(state);
+                re.to_bits().hash(state);
+                im.to_bits().hash(state);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Symbol {
+    /// Renders by name only — a bound symbol's value doesn't round-trip
+    /// through `Display`, since the infix parser has no binding syntax.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Symbol::Named(name) => write!(f, "{name}"),
+            Symbol::Bound { name, .. } => write!(f, "{name}"),
+            Symbol::BoundComplex { name, .. } => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Symbol {
+    /// Builds a `Named` symbol, interning `name` so repeated uses of the
+    /// same parameter name share one backing allocation.
+    pub fn new(name: &str) -> Symbol {
+        Symbol::Named(intern(name))
+    }
+
+    /// The symbol's name, whether `Named`, `Bound`, or `BoundComplex`.
+    pub fn name(&self) -> &str {
+        match self {
+            Symbol::Named(name) => name,
+            Symbol::Bound { name, .. } => name,
+            Symbol::BoundComplex { name, .. } => name,
+        }
+    }
+
+    /// Converts this symbol into a `Bound` with the given `value`, keeping
+    /// the name. Rebinds an already-bound symbol to the new value.
+    pub fn bind(self, value: f64) -> Symbol {
+        let name = self.into_name();
+        Symbol::Bound { name, value }
+    }
+
+    /// Converts this symbol into a `BoundComplex` with the given `re + im*i`
+    /// value, keeping the name. Rebinds an already-bound symbol (real or
+    /// complex) to the new value.
+    pub fn bind_complex(self, re: f64, im: f64) -> Symbol {
+        let name = self.into_name();
+        Symbol::BoundComplex { name, re, im }
+    }
+
+    fn into_name(self) -> Arc<str> {
+        match self {
+            Symbol::Named(name) => name,
+            Symbol::Bound { name, .. } => name,
+            Symbol::BoundComplex { name, .. } => name,
+        }
+    }
+
+    /// The bound real value, or `None` if this symbol is free or bound to a
+    /// complex value. Use [`Symbol::complex_value`] to read either kind.
+    pub fn value(&self) -> Option<f64> {
+        match self {
+            Symbol::Named(_) | Symbol::BoundComplex { .. } => None,
+            Symbol::Bound { value, .. } => Some(*value),
+        }
+    }
+
+    /// The bound value as `(re, im)`, whether `Bound` (in which case
+    /// `im == 0.0`) or `BoundComplex`, or `None` if this symbol is free.
+    pub fn complex_value(&self) -> Option<(f64, f64)> {
+        match self {
+            Symbol::Named(_) => None,
+            Symbol::Bound { value, .. } => Some((*value, 0.0)),
+            Symbol::BoundComplex { re, im, .. } => Some((*re, *im)),
+        }
+    }
+
+    /// Strips a bound value, returning the pure `Named` form. Leaves an
+    /// already-`Named` symbol unchanged.
+    pub fn unbind(self) -> Symbol {
+        Symbol::Named(self.into_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_preserves_name() {
+        let bound = Symbol::new("theta").bind(1.5);
+        assert_eq!(
+            bound,
+            Symbol::Bound {
+                name: Arc::from("theta"),
+                value: 1.5
+            }
+        );
+    }
+
+    #[test]
+    fn value_is_none_for_named() {
+        assert_eq!(Symbol::new("theta").value(), None);
+        assert_eq!(Symbol::new("theta").bind(1.0).value(), Some(1.0));
+    }
+
+    #[test]
+    fn bind_complex_round_trips_through_complex_value() {
+        let bound = Symbol::new("phi").bind_complex(1.0, -2.0);
+        assert_eq!(bound.complex_value(), Some((1.0, -2.0)));
+        assert_eq!(bound.value(), None);
+        assert_eq!(bound.name(), "phi");
+    }
+
+    #[test]
+    fn complex_value_of_real_bound_has_zero_imaginary_part() {
+        assert_eq!(Symbol::new("theta").bind(3.0).complex_value(), Some((3.0, 0.0)));
+        assert_eq!(Symbol::new("theta").complex_value(), None);
+    }
+
+    #[test]
+    fn unbind_round_trip() {
+        let sym = Symbol::new("theta");
+        assert_eq!(sym.clone().bind(1.0).unbind(), sym);
+    }
+
+    #[test]
+    fn unbind_strips_any_bound_value() {
+        let bound = Symbol::Bound {
+            name: Arc::from("phi"),
+            value: 2.5,
+        };
+        assert_eq!(bound.unbind(), Symbol::new("phi"));
+    }
+
+    #[test]
+    fn bound_and_unbound_symbols_are_not_equal() {
+        let named = Symbol::new("theta");
+        let bound = Symbol::new("theta").bind(1.0);
+        assert_ne!(named, bound);
+    }
+
+    #[test]
+    fn display_shows_name_only() {
+        assert_eq!(Symbol::new("theta").to_string(), "theta");
+        assert_eq!(Symbol::new("theta").bind(1.5).to_string(), "theta");
+    }
+
+    #[test]
+    fn name_returns_str_for_both_variants() {
+        assert_eq!(Symbol::new("theta").name(), "theta");
+        assert_eq!(Symbol::new("theta").bind(1.0).name(), "theta");
+    }
+
+    #[test]
+    fn ordering_puts_named_before_bound_and_breaks_ties_by_value() {
+        let named = Symbol::new("theta");
+        let bound = Symbol::new("theta").bind(1.0);
+        assert!(named < bound);
+
+        let low = Symbol::new("theta").bind(1.0);
+        let high = Symbol::new("theta").bind(2.0);
+        assert!(low < high);
+    }
+
+    #[test]
+    fn hash_set_distinguishes_bound_from_unbound() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(Symbol::new("theta"));
+        set.insert(Symbol::new("theta").bind(1.0));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn new_interns_so_equal_names_share_backing() {
+        let a = Symbol::new("theta");
+        let b = Symbol::new("theta");
+        match (a, b) {
+            (Symbol::Named(a), Symbol::Named(b)) => assert!(Arc::ptr_eq(&a, &b)),
+            _ => panic!("expected Named symbols"),
+        }
+    }
+}
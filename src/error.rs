@@ -0,0 +1,55 @@
+//! The crate-wide error type returned by fallible operations.
+
+use std::fmt;
+
+/// A structured error from a fallible `cation` operation, in place of a
+/// plain `String`, so callers can match on specific failure modes instead
+/// of parsing messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CationError {
+    /// An unrecognized single-letter Pauli operator character, e.g. the
+    /// `'T'` in `"IIT"`.
+    InvalidPauliChar(char),
+    /// The same qubit or mode index was given twice where indices must be
+    /// distinct, carrying a description of where the collision happened.
+    DuplicateIndex(usize, String),
+    /// A symbolic name has no bound value where a concrete number was
+    /// required to proceed.
+    UnboundSymbol(String),
+    /// An argument's size or shape didn't match what an operation required.
+    DimensionMismatch(String),
+    /// Any other failure, carrying a human-readable message. Used for
+    /// parse errors and other cases too varied to name individually.
+    Other(String),
+}
+
+impl fmt::Display for CationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CationError::InvalidPauliChar(c) => write!(f, "unknown Pauli operator character {c:?}"),
+            CationError::DuplicateIndex(index, context) => write!(f, "duplicate qubit index {index} {context}"),
+            CationError::UnboundSymbol(name) => write!(f, "cannot evaluate unbound symbol {name:?}"),
+            CationError::DimensionMismatch(msg) => write!(f, "{msg}"),
+            CationError::Other(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_pauli_char_displays_the_offending_character() {
+        let err = CationError::InvalidPauliChar('T');
+        assert_eq!(err.to_string(), "unknown Pauli operator character 'T'");
+    }
+
+    #[test]
+    fn duplicate_index_displays_index_and_context() {
+        let err = CationError::DuplicateIndex(3, "in PauliString".to_string());
+        assert_eq!(err.to_string(), "duplicate qubit index 3 in PauliString");
+    }
+}
@@ -0,0 +1,123 @@
+//! Fermionic creation/annihilation operators and their Jordan-Wigner
+//! mapping onto qubit Pauli strings.
+
+use std::sync::Arc;
+
+use crate::core_ir::{Expr, Pauli, PauliString};
+use crate::error::CationError;
+
+/// A single fermionic ladder or number operator acting on one mode in
+/// second quantization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FermionOp {
+    Create(usize),
+    Annihilate(usize),
+    Number(usize),
+}
+
+/// Maps a fermionic operator on mode `mode < num_modes` to its
+/// Jordan-Wigner image as a qubit `Expr`.
+///
+/// `a_j^dagger a_j` reduces to the Hermitian, real-coefficient
+/// `0.5*(I - Z_j)` with no Z-string needed (the strings on lower modes
+/// cancel). `Create`/`Annihilate` are non-Hermitian and map to the
+/// standard Z-string-dressed `0.5*(X_j -+ i*Y_j)`, with a `Z`-string on
+/// every mode below `j` to account for fermionic anticommutation; the
+/// `i` coefficient is an [`Expr::Complex`].
+pub fn jordan_wigner(op: &FermionOp, num_modes: usize) -> Result<Arc<Expr>, CationError> {
+    let mode = match op {
+        FermionOp::Create(m) | FermionOp::Annihilate(m) | FermionOp::Number(m) => *m,
+    };
+    if mode >= num_modes {
+        return Err(CationError::DimensionMismatch(format!(
+            "mode {mode} out of range for {num_modes} modes"
+        )));
+    }
+
+    match op {
+        FermionOp::Number(mode) => {
+            let identity = Arc::new(Expr::Pauli(PauliString::identity()));
+            let z = Arc::new(Expr::Pauli(PauliString::new([(*mode, Pauli::Z)])));
+            let neg_z = Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(-1.0)), z]));
+            let diff = Arc::new(Expr::Sum(vec![identity, neg_z]));
+            Ok(Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(0.5)), diff])))
+        }
+        FermionOp::Create(mode) | FermionOp::Annihilate(mode) => {
+            // a_j = Z-string(<j) * 0.5*(X_j + i*Y_j), a_j^dagger is its
+            // dagger: same Z-string, 0.5*(X_j - i*Y_j).
+            let sign = if matches!(op, FermionOp::Create(_)) { -1.0 } else { 1.0 };
+            let mut x_ops: Vec<(usize, Pauli)> = (0..*mode).map(|k| (k, Pauli::Z)).collect();
+            let mut y_ops = x_ops.clone();
+            x_ops.push((*mode, Pauli::X));
+            y_ops.push((*mode, Pauli::Y));
+            let x_term = Arc::new(Expr::Product(vec![
+                Arc::new(Expr::Complex(0.5, 0.0)),
+                Arc::new(Expr::Pauli(PauliString::new(x_ops))),
+            ]));
+            let y_term = Arc::new(Expr::Product(vec![
+                Arc::new(Expr::Complex(0.0, 0.5 * sign)),
+                Arc::new(Expr::Pauli(PauliString::new(y_ops))),
+            ]));
+            Ok(Arc::new(Expr::Sum(vec![x_term, y_term])))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_operator_maps_to_half_identity_minus_z() {
+        let expr = jordan_wigner(&FermionOp::Number(0), 1).unwrap();
+        let identity = Arc::new(Expr::Pauli(PauliString::new([])));
+        let z0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z)])));
+        let expected = Arc::new(Expr::Product(vec![
+            Arc::new(Expr::Scalar(0.5)),
+            Arc::new(Expr::Sum(vec![
+                identity,
+                Arc::new(Expr::Product(vec![Arc::new(Expr::Scalar(-1.0)), z0])),
+            ])),
+        ]));
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn annihilate_maps_to_half_x_plus_i_y() {
+        let expr = jordan_wigner(&FermionOp::Annihilate(0), 1).unwrap();
+        let x0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::X)])));
+        let y0 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Y)])));
+        let expected = Arc::new(Expr::Sum(vec![
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Complex(0.5, 0.0)), x0])),
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Complex(0.0, 0.5)), y0])),
+        ]));
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn create_is_the_dagger_of_annihilate() {
+        let create = jordan_wigner(&FermionOp::Create(0), 1).unwrap();
+        let annihilate = jordan_wigner(&FermionOp::Annihilate(0), 1).unwrap();
+        assert_eq!(
+            create.canonical_algebraic(),
+            annihilate.dagger().canonical_algebraic()
+        );
+    }
+
+    #[test]
+    fn create_and_annihilate_carry_a_z_string_on_lower_modes() {
+        let expr = jordan_wigner(&FermionOp::Annihilate(1), 2).unwrap();
+        let z0x1 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z), (1, Pauli::X)])));
+        let z0y1 = Arc::new(Expr::Pauli(PauliString::new([(0, Pauli::Z), (1, Pauli::Y)])));
+        let expected = Arc::new(Expr::Sum(vec![
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Complex(0.5, 0.0)), z0x1])),
+            Arc::new(Expr::Product(vec![Arc::new(Expr::Complex(0.0, 0.5)), z0y1])),
+        ]));
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn out_of_range_mode_errors() {
+        assert!(jordan_wigner(&FermionOp::Number(2), 2).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+//! `cation` is a symbolic algebra crate for composing and simplifying
+//! Pauli-operator Hamiltonians.
+
+pub mod clifford;
+pub mod core_ir;
+pub mod error;
+pub mod fermion;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use core_ir::{Expr, Pauli, PauliString, Symbol};
+pub use error::CationError;
+
+/// Parses infix `Expr` syntax at the call site, e.g.
+/// `expr!(2.0 * X0 + theta * Z1)`, returning an `Arc<Expr>`.
+///
+/// This is a thin macro wrapper around [`Expr::parse`]: `macro_rules!`
+/// can't tell a bare Pauli token like `X0` apart from an ordinary symbol
+/// by its spelling alone without inspecting the identifier's text, which
+/// only the runtime tokenizer does — so `expr!` stringifies its input and
+/// parses it through that same grammar, panicking on malformed input.
+/// It exists to avoid a string literal and a `.unwrap()` at every call
+/// site, not to duplicate the parser's grammar at compile time.
+#[macro_export]
+macro_rules! expr {
+    ($($tt:tt)+) => {
+        $crate::Expr::parse(stringify!($($tt)+)).expect("expr! macro input failed to parse")
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn expr_macro_matches_runtime_parser() {
+        let via_macro = expr!(2.0 * X0 + theta * Z1);
+        let via_parser = crate::Expr::parse("2.0 * X0 + theta * Z1").unwrap();
+        assert_eq!(via_macro, via_parser);
+    }
+}
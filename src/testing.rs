@@ -0,0 +1,139 @@
+//! Random generators for property-based testing, feature-gated behind
+//! `testing` so `rand`/`proptest`/`quickcheck` aren't pulled into default
+//! builds. Lets downstream crates fuzz transforms like canonicalization
+//! across many well-formed `PauliString`/`Expr` values instead of hand-
+//! writing a handful of fixed cases.
+
+use std::sync::Arc;
+
+use rand::{Rng, RngExt};
+
+use crate::core_ir::{Expr, Pauli, PauliString};
+
+/// Builds a random `PauliString` over `num_qubits` qubits with `weight`
+/// non-identity operators (clamped to `num_qubits` if larger), choosing
+/// distinct qubit indices and a uniformly random non-identity Pauli for
+/// each.
+pub fn random_pauli_string(rng: &mut impl Rng, num_qubits: usize, weight: usize) -> PauliString {
+    let weight = weight.min(num_qubits);
+    let mut qubits: Vec<usize> = (0..num_qubits).collect();
+    for i in 0..weight {
+        let j = rng.random_range(i..num_qubits);
+        qubits.swap(i, j);
+    }
+    let ops = qubits[..weight].iter().map(|&qubit| {
+        let pauli = match rng.random_range(0..3) {
+            0 => Pauli::X,
+            1 => Pauli::Y,
+            _ => Pauli::Z,
+        };
+        (qubit, pauli)
+    });
+    PauliString::new(ops)
+}
+
+/// Builds a random `Expr` tree over `num_qubits` qubits, recursing through
+/// `Sum`/`Product` nodes up to `depth` levels before bottoming out at a
+/// `Pauli` or `Scalar` leaf (`depth == 0` always produces a leaf directly).
+pub fn random_expr(rng: &mut impl Rng, depth: usize, num_qubits: usize) -> Arc<Expr> {
+    if depth == 0 {
+        return random_leaf(rng, num_qubits);
+    }
+    let branching = rng.random_range(2..=3);
+    let children: Vec<Arc<Expr>> = (0..branching).map(|_| random_expr(rng, depth - 1, num_qubits)).collect();
+    if rng.random_bool(0.5) {
+        Arc::new(Expr::Sum(children))
+    } else {
+        Arc::new(Expr::Product(children))
+    }
+}
+
+fn random_leaf(rng: &mut impl Rng, num_qubits: usize) -> Arc<Expr> {
+    if num_qubits > 0 && rng.random_bool(0.5) {
+        let weight = rng.random_range(0..=num_qubits.min(3));
+        Arc::new(Expr::Pauli(random_pauli_string(rng, num_qubits, weight)))
+    } else {
+        Arc::new(Expr::Scalar(rng.random_range(-4.0..4.0)))
+    }
+}
+
+impl proptest::arbitrary::Arbitrary for PauliString {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<PauliString>;
+
+    /// Generates a `PauliString` by picking a random-length run of
+    /// non-identity Paulis on qubits `0..len`; qubit indices are the
+    /// sequence position, so they're always distinct by construction.
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::*;
+        proptest::collection::vec(0u8..3, 0..6)
+            .prop_map(|choices| {
+                let ops = choices.into_iter().enumerate().map(|(qubit, choice)| {
+                    let pauli = match choice {
+                        0 => Pauli::X,
+                        1 => Pauli::Y,
+                        _ => Pauli::Z,
+                    };
+                    (qubit, pauli)
+                });
+                PauliString::new(ops)
+            })
+            .boxed()
+    }
+}
+
+impl quickcheck::Arbitrary for PauliString {
+    /// Mirrors the `proptest` impl above: a random-length run of
+    /// non-identity Paulis on qubits `0..len`.
+    fn arbitrary(g: &mut quickcheck::Gen) -> PauliString {
+        let weight = usize::arbitrary(g) % 6;
+        let ops = (0..weight).map(|qubit| {
+            let pauli = match u8::arbitrary(g) % 3 {
+                0 => Pauli::X,
+                1 => Pauli::Y,
+                _ => Pauli::Z,
+            };
+            (qubit, pauli)
+        });
+        PauliString::new(ops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_ir::Canonical;
+
+    #[test]
+    fn random_pauli_string_has_the_requested_weight() {
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let ps = random_pauli_string(&mut rng, 8, 5);
+            assert_eq!(ps.weight(), 5);
+        }
+    }
+
+    #[test]
+    fn canonical_is_idempotent_across_random_exprs() {
+        let mut rng = rand::rng();
+        for _ in 0..200 {
+            let expr = random_expr(&mut rng, 3, 4);
+            let once = expr.canonical();
+            let twice = once.clone().into_inner().canonical();
+            assert_eq!(once, twice);
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn arbitrary_pauli_string_support_matches_its_weight(ps: PauliString) {
+            proptest::prop_assert_eq!(ps.support().len(), ps.weight());
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn arbitrary_pauli_string_is_identity_iff_zero_weight(ps: PauliString) -> bool {
+            ps.is_identity() == (ps.weight() == 0)
+        }
+    }
+}